@@ -132,10 +132,49 @@ fn criterion_benchmark_bundle_decode(c: &mut Criterion) {
         })
     });
 }
+fn criterion_benchmark_bundle_large_payload(c: &mut Criterion) {
+    let dst = eid::EndpointID::with_dtn("node2/inbox").unwrap();
+    let src = eid::EndpointID::with_dtn("node1/123456").unwrap();
+    let now = dtntime::CreationTimestamp::with_time_and_seq(dtntime::dtn_time_now(), 0);
+
+    let pblock = primary::PrimaryBlockBuilder::default()
+        .destination(dst)
+        .source(src.clone())
+        .report_to(src)
+        .creation_timestamp(now)
+        .lifetime(std::time::Duration::from_secs(60 * 60))
+        .build()
+        .unwrap();
+    let mut b = bundle::Bundle::new(
+        pblock,
+        vec![canonical::new_payload_block(
+            BlockControlFlags::empty(),
+            vec![0x42; 1024 * 1024],
+        )],
+    );
+
+    for (name, crc_type) in [("crc 16", crc::CRC_16), ("crc 32", crc::CRC_32)] {
+        b.set_crc(crc_type);
+        let mut bndl = b.clone();
+        c.bench_function(&format!("encode 1 MiB bundle {}", name), move |bench| {
+            bench.iter(|| bndl.to_cbor())
+        });
+
+        let encoded = b.to_cbor();
+        c.bench_function(&format!("decode 1 MiB bundle {}", name), move |bench| {
+            bench.iter(|| {
+                let _deserialized: Bundle = Bundle::try_from(encoded.as_slice()).unwrap();
+                _deserialized.validate().unwrap();
+            })
+        });
+    }
+}
+
 criterion_group!(
     benches,
     criterion_benchmark_bundle_create,
     criterion_benchmark_bundle_encode,
-    criterion_benchmark_bundle_decode
+    criterion_benchmark_bundle_decode,
+    criterion_benchmark_bundle_large_payload
 );
 criterion_main!(benches);
@@ -1,5 +1,12 @@
 #include "bp7.h"
 #include <stdio.h>
+#include <string.h>
+
+static void print_last_error(const char *what) {
+  char *msg = bp7_last_error_message();
+  printf("%s failed (code %d): %s\n", what, bp7_last_error_code(), msg);
+  bp7_string_free(msg);
+}
 
 int main() {
   printf("bp7 c ffi test\n");
@@ -12,21 +19,68 @@ int main() {
 
   printf("parsing bundle again from cbor buffer...\n");
   Bundle *bndl = bundle_from_cbor(buf);
+  if (bndl == NULL) {
+    print_last_error("bundle_from_cbor");
+    buffer_free(buf);
+    return 1;
+  }
 
   printf("getting metadata from parsed bundle...\n");
   BundleMetaData *meta = bundle_get_metadata(bndl);
   printf(" meta.src: %s\n", meta->src);
   printf(" meta.dst: %s\n", meta->dst);
+  printf(" meta.summary: %s\n", meta->summary);
   bundle_metadata_free(meta);
 
   printf("getting payload from parsed bundle...\n");
   Buffer *payload = bundle_payload(bndl);
-  printf(" payload: %s\n", payload->data);
+  printf(" payload: %.*s\n", payload->len, payload->data);
 
   buffer_free(payload);
 
   bundle_free(bndl);
   buffer_free(buf);
   //}
+
+  printf("building bundle with extension blocks...\n");
+  PrimaryBlockBuilder *builder = primary_builder_new();
+  if (primary_builder_destination(builder, "not an endpoint") != Bp7ErrorCode_Ok) {
+    print_last_error(" primary_builder_destination");
+  }
+  primary_builder_destination(builder, "dtn://node2/inbox");
+  primary_builder_source(builder, "dtn://node1/app");
+  primary_builder_report_to(builder, "dtn://node1/app");
+  primary_builder_flags(builder, 0x20000); // request delivery status reports
+
+  char text[] = "hello from c";
+  Buffer data = {(uint8_t *)text, strlen(text)};
+  bndl = bundle_new(builder, &data, 2);
+  primary_builder_free(builder);
+  bundle_add_hop_count_block(bndl, 32);
+  bundle_add_previous_node_block(bndl, "dtn://node1/");
+  bundle_update_extensions(bndl, "dtn://node2/", 10);
+
+  uint8_t limit, count;
+  if (bundle_hop_count(bndl, &limit, &count) == Bp7ErrorCode_Ok) {
+    printf(" hop count: %d/%d\n", count, limit);
+  }
+  char *node = bundle_previous_node(bndl);
+  printf(" previous node: %s\n", node);
+  bp7_string_free(node);
+  if (bundle_validate(bndl) != Bp7ErrorCode_Ok) {
+    print_last_error(" bundle_validate");
+  }
+
+  printf("creating delivery status report...\n");
+  Bundle *report = bundle_new_status_report(bndl, "dtn://node2/", 1, 1 << 2, 0, false);
+  if (report == NULL) {
+    print_last_error(" bundle_new_status_report");
+  } else {
+    StatusReportInfo *info = bundle_status_report(report);
+    printf(" report for %s, asserted mask: %d\n", info->refbundle, info->asserted);
+    status_report_info_free(info);
+    bundle_free(report);
+  }
+  bundle_free(bndl);
   return 0;
-}
\ No newline at end of file
+}
@@ -1,13 +1,16 @@
 use crate::bundle::Bundle;
 use crate::bundle::ByteBuffer;
+use crate::error::Error;
 use crate::flags::BlockControlFlags;
 use crate::flags::BundleControlFlags;
 use crate::flags::BundleValidation;
 use crate::{bundle, crc, dtn_time_now, primary};
+use core::convert::TryFrom;
 use core::fmt;
 use serde::de::{SeqAccess, Visitor};
-use serde::ser::{SerializeSeq, Serializer};
+use serde::ser::{self, SerializeSeq, Serializer};
 use serde::{Deserialize, Deserializer, Serialize, de};
+use thiserror::Error;
 
 use crate::dtntime::CreationTimestamp;
 use crate::dtntime::DtnTime;
@@ -17,13 +20,39 @@ pub type AdministrativeRecordTypeCode = u32;
 
 pub const BUNDLE_STATUS_REPORT_TYPE_CODE: AdministrativeRecordTypeCode = 1;
 
+/// Type code of a Bundle-in-Bundle Encapsulation protocol data unit (BPDU).
+pub const BIBE_PROTOCOL_DATA_UNIT_TYPE_CODE: AdministrativeRecordTypeCode = 3;
+
+/// Type code of a BIBE custody signal.
+pub const CUSTODY_SIGNAL_TYPE_CODE: AdministrativeRecordTypeCode = 4;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AdministrativeRecord {
     BundleStatusReport(StatusReport),
+    BibeProtocolDataUnit(BibePdu),
+    CustodySignal(CustodySignal),
     Unknown(AdministrativeRecordTypeCode, ByteBuffer),
+    /// Known type code whose content does not match the record structure, holds the CBOR encoded
+    /// content item as received, see [`AdministrativeRecord::from_cbor`].
     Mismatched(AdministrativeRecordTypeCode, ByteBuffer),
 }
 
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AdministrativeRecordError {
+    #[error("bundle is not flagged as administrative record")]
+    NotAdministrativeRecord,
+    #[error("missing payload block")]
+    MissingPayload,
+    #[error("bundle is flagged as administrative record, but payload is not one: {0}")]
+    Mismatched(String),
+    #[error("payload is an administrative record of type `{0}`, but bundle is not flagged as one")]
+    Unflagged(AdministrativeRecordTypeCode),
+    #[error("malformed status report")]
+    MalformedStatusReport,
+    #[error("malformed administrative record of type `{0}`")]
+    Malformed(AdministrativeRecordTypeCode),
+}
+
 impl Serialize for AdministrativeRecord {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -35,13 +64,25 @@ impl Serialize for AdministrativeRecord {
                 seq.serialize_element(&BUNDLE_STATUS_REPORT_TYPE_CODE)?;
                 seq.serialize_element(&sr)?;
             }
+            AdministrativeRecord::BibeProtocolDataUnit(bpdu) => {
+                seq.serialize_element(&BIBE_PROTOCOL_DATA_UNIT_TYPE_CODE)?;
+                seq.serialize_element(&bpdu)?;
+            }
+            AdministrativeRecord::CustodySignal(cs) => {
+                seq.serialize_element(&CUSTODY_SIGNAL_TYPE_CODE)?;
+                seq.serialize_element(&cs)?;
+            }
             AdministrativeRecord::Unknown(code, data) => {
                 seq.serialize_element(&code)?;
                 seq.serialize_element(&serde_bytes::Bytes::new(data))?;
             }
             AdministrativeRecord::Mismatched(code, data) => {
                 seq.serialize_element(&code)?;
-                seq.serialize_element(&serde_bytes::Bytes::new(data))?;
+                // serializers cannot take encoded items, AdministrativeRecord::to_cbor writes them verbatim
+                let content = serde_cbor::from_slice::<serde_cbor::Value>(data).map_err(|_| {
+                    ser::Error::custom("content of mismatched record is not a single CBOR item")
+                })?;
+                seq.serialize_element(&content)?;
             }
         }
 
@@ -71,12 +112,32 @@ impl<'de> Deserialize<'de> for AdministrativeRecord {
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(0, &self))?;
 
-                if code == BUNDLE_STATUS_REPORT_TYPE_CODE {
-                    let sr: StatusReport = seq
+                if code == BUNDLE_STATUS_REPORT_TYPE_CODE
+                    || code == BIBE_PROTOCOL_DATA_UNIT_TYPE_CODE
+                    || code == CUSTODY_SIGNAL_TYPE_CODE
+                {
+                    // decode known records in two steps to preserve malformed content as mismatched
+                    let content: serde_cbor::Value = seq
                         .next_element()?
                         .ok_or_else(|| de::Error::invalid_length(1, &self))?;
-                    // TODO: check for mixmatched
-                    Ok(AdministrativeRecord::BundleStatusReport(sr))
+                    let record = if code == BUNDLE_STATUS_REPORT_TYPE_CODE {
+                        serde_cbor::value::from_value(content.clone())
+                            .map(AdministrativeRecord::BundleStatusReport)
+                    } else if code == BIBE_PROTOCOL_DATA_UNIT_TYPE_CODE {
+                        serde_cbor::value::from_value(content.clone())
+                            .map(AdministrativeRecord::BibeProtocolDataUnit)
+                    } else {
+                        serde_cbor::value::from_value(content.clone())
+                            .map(AdministrativeRecord::CustodySignal)
+                    };
+                    // re-encoded content, AdministrativeRecord::from_cbor keeps the received bytes
+                    match record {
+                        Ok(record) => Ok(record),
+                        Err(_) => Ok(AdministrativeRecord::Mismatched(
+                            code,
+                            serde_cbor::to_vec(&content).map_err(de::Error::custom)?,
+                        )),
+                    }
                 } else {
                     let data: ByteBuffer = seq
                         .next_element::<serde_bytes::ByteBuf>()?
@@ -93,10 +154,86 @@ impl<'de> Deserialize<'de> for AdministrativeRecord {
 }
 
 impl AdministrativeRecord {
+    pub fn type_code(&self) -> AdministrativeRecordTypeCode {
+        match self {
+            AdministrativeRecord::BundleStatusReport(_) => BUNDLE_STATUS_REPORT_TYPE_CODE,
+            AdministrativeRecord::BibeProtocolDataUnit(_) => BIBE_PROTOCOL_DATA_UNIT_TYPE_CODE,
+            AdministrativeRecord::CustodySignal(_) => CUSTODY_SIGNAL_TYPE_CODE,
+            AdministrativeRecord::Unknown(code, _) => *code,
+            AdministrativeRecord::Mismatched(code, _) => *code,
+        }
+    }
+    /// Decode a CBOR encoded record, keeping the received content of mismatched records.
+    pub fn from_cbor(data: &[u8]) -> Result<AdministrativeRecord, serde_cbor::Error> {
+        let mut record: AdministrativeRecord = serde_cbor::from_slice(data)?;
+        if let AdministrativeRecord::Mismatched(_, content) = &mut record {
+            let (_, mut pos) = bundle::cbor_array_header(data)?;
+            let mut de = serde_cbor::Deserializer::from_slice(&data[pos..]);
+            AdministrativeRecordTypeCode::deserialize(&mut de)?;
+            pos += de.byte_offset();
+            let mut de = serde_cbor::Deserializer::from_slice(&data[pos..]);
+            de::IgnoredAny::deserialize(&mut de)?;
+            *content = data[pos..pos + de.byte_offset()].to_vec();
+        }
+        Ok(record)
+    }
+    /// Encode the record, the content of mismatched records is written verbatim.
+    pub fn to_cbor(&self) -> ByteBuffer {
+        match self {
+            AdministrativeRecord::Mismatched(code, content) => {
+                let mut data = vec![0x82];
+                data.extend(serde_cbor::to_vec(code).unwrap());
+                data.extend_from_slice(content);
+                data
+            }
+            _ => serde_cbor::to_vec(&self).unwrap(),
+        }
+    }
     pub fn to_payload(&self) -> crate::canonical::CanonicalBlock {
-        let data: ByteBuffer = serde_cbor::to_vec(&self).unwrap();
+        crate::canonical::new_payload_block(BlockControlFlags::empty(), self.to_cbor())
+    }
+}
 
-        crate::canonical::new_payload_block(BlockControlFlags::empty(), data)
+/// One line description of the record, e.g. `status report for <bundle id>: delivered (no_information)`.
+impl fmt::Display for AdministrativeRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdministrativeRecord::BundleStatusReport(sr) => {
+                let asserted: Vec<&str> = (0..MAX_STATUS_INFORMATION_POS)
+                    .filter(|pos| sr.is_asserted(*pos))
+                    .filter_map(status_information_name)
+                    .collect();
+                write!(
+                    f,
+                    "status report for {}: {} (",
+                    sr.refbundle(),
+                    asserted.join(", ")
+                )?;
+                match status_report_reason_name(sr.report_reason) {
+                    Some(reason) => write!(f, "{})", reason),
+                    None => write!(f, "{})", sr.report_reason),
+                }
+            }
+            AdministrativeRecord::BibeProtocolDataUnit(bpdu) => match bpdu.bundle() {
+                Ok(b) => write!(f, "BIBE PDU {} carrying {}", bpdu.transmission_id, b.id()),
+                Err(_) => write!(
+                    f,
+                    "BIBE PDU {} carrying undecodable bundle",
+                    bpdu.transmission_id
+                ),
+            },
+            AdministrativeRecord::CustodySignal(cs) => write!(
+                f,
+                "custody signal with {} disposition(s)",
+                cs.dispositions.len()
+            ),
+            AdministrativeRecord::Unknown(code, data) => {
+                write!(f, "unknown record type {} ({} bytes)", code, data.len())
+            }
+            AdministrativeRecord::Mismatched(code, data) => {
+                write!(f, "malformed record type {} ({} bytes)", code, data.len())
+            }
+        }
     }
 }
 // Bundle Status Report
@@ -139,6 +276,30 @@ pub const TRAFFIC_PARED: StatusReportReason = 10;
 /// BLOCK_UNSUPPORTED bundle status report reason code.
 pub const BLOCK_UNSUPPORTED: StatusReportReason = 11;
 
+/// Returns the RFC 9171 name of a status report reason code, e.g., `lifetime_expired`.
+pub fn status_report_reason_name(reason: StatusReportReason) -> Option<&'static str> {
+    match reason {
+        NO_INFORMATION => Some("no_information"),
+        LIFETIME_EXPIRED => Some("lifetime_expired"),
+        FORWARD_UNIDIRECTIONAL_LINK => Some("forward_unidirectional_link"),
+        TRANSMISSION_CANCELED => Some("transmission_canceled"),
+        DEPLETED_STORAGE => Some("depleted_storage"),
+        DEST_ENDPOINT_UNINTELLIGIBLE => Some("dest_endpoint_unintelligible"),
+        NO_ROUTE_TO_DESTINATION => Some("no_route_to_destination"),
+        NO_NEXT_NODE_CONTACT => Some("no_next_node_contact"),
+        BLOCK_UNINTELLIGIBLE => Some("block_unintelligible"),
+        HOP_LIMIT_EXCEEDED => Some("hop_limit_exceeded"),
+        TRAFFIC_PARED => Some("traffic_pared"),
+        BLOCK_UNSUPPORTED => Some("block_unsupported"),
+        _ => None,
+    }
+}
+
+/// Looks up a status report reason code by its name, see [`status_report_reason_name`].
+pub fn status_report_reason_from_name(name: &str) -> Option<StatusReportReason> {
+    (NO_INFORMATION..=BLOCK_UNSUPPORTED).find(|r| status_report_reason_name(*r) == Some(name))
+}
+
 // BundleStatusItem represents the a bundle status item, as used as an element
 // in the bundle status information array of each Bundle Status Report.
 #[derive(Debug, Clone, PartialEq)]
@@ -252,6 +413,22 @@ pub const DELIVERED_BUNDLE: StatusInformationPos = 2;
 // DELETED_BUNDLE is the fourth bundle status information entry, indicating the reporting node deleted this bundle.
 pub const DELETED_BUNDLE: StatusInformationPos = 3;
 
+/// Returns the name of a status information entry, e.g., `delivered`.
+pub fn status_information_name(status: StatusInformationPos) -> Option<&'static str> {
+    match status {
+        RECEIVED_BUNDLE => Some("received"),
+        FORWARDED_BUNDLE => Some("forwarded"),
+        DELIVERED_BUNDLE => Some("delivered"),
+        DELETED_BUNDLE => Some("deleted"),
+        _ => None,
+    }
+}
+
+/// Looks up a status information entry by its name, see [`status_information_name`].
+pub fn status_information_from_name(name: &str) -> Option<StatusInformationPos> {
+    (0..MAX_STATUS_INFORMATION_POS).find(|s| status_information_name(*s) == Some(name))
+}
+
 // StatusReport is the bundle status report, used in an administrative record.
 #[derive(Debug, Clone, PartialEq)]
 pub struct StatusReport {
@@ -264,6 +441,12 @@ pub struct StatusReport {
 }
 
 impl StatusReport {
+    /// Checks whether the given status information is asserted in this report.
+    pub fn is_asserted(&self, status: StatusInformationPos) -> bool {
+        self.status_information
+            .get(status as usize)
+            .is_some_and(|item| item.asserted)
+    }
     pub fn refbundle(&self) -> String {
         let mut id = format!(
             "{}-{}-{}",
@@ -357,6 +540,75 @@ impl<'de> Deserialize<'de> for StatusReport {
     }
 }
 
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum StatusReportError {
+    #[error("no status assertion given")]
+    NoAssertion,
+    #[error("invalid status information position `{0}`")]
+    InvalidStatusInformation(StatusInformationPos),
+    #[error("status report for status information `{0}` was not requested")]
+    NotRequested(StatusInformationPos),
+    #[error("report-to endpoint is dtn:none")]
+    ReportToNone,
+    #[error("source endpoint is dtn:none, bundle is not uniquely identifiable")]
+    AnonymousSource,
+    #[error("no status reports are generated for administrative records")]
+    AdministrativeRecord,
+    #[error("invalid status report bundle: {0}")]
+    InvalidBundle(String),
+}
+
+impl From<primary::PrimaryBuilderError> for StatusReportError {
+    fn from(err: primary::PrimaryBuilderError) -> Self {
+        match err {
+            primary::PrimaryBuilderError::NoDestination => StatusReportError::ReportToNone,
+            err => StatusReportError::InvalidBundle(err.to_string()),
+        }
+    }
+}
+
+/// Returns the bundle control flag requesting reports for the given status information.
+pub fn status_request_flag(status: StatusInformationPos) -> Option<BundleControlFlags> {
+    match status {
+        RECEIVED_BUNDLE => Some(BundleControlFlags::BUNDLE_STATUS_REQUEST_RECEPTION),
+        FORWARDED_BUNDLE => Some(BundleControlFlags::BUNDLE_STATUS_REQUEST_FORWARD),
+        DELIVERED_BUNDLE => Some(BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY),
+        DELETED_BUNDLE => Some(BundleControlFlags::BUNDLE_STATUS_REQUEST_DELETION),
+        _ => None,
+    }
+}
+
+/// Checks whether the given bundle requested status reports for the given status information.
+pub fn is_status_requested(bndl: &Bundle, status: StatusInformationPos) -> bool {
+    status_request_flag(status).is_some_and(|flag| bndl.primary.bundle_control_flags.contains(flag))
+}
+
+/// Checks whether RFC 9171 permits a status report asserting `status` for the given bundle.
+///
+/// Reports are suppressed if they were not requested, if the bundle is an administrative
+/// record, or if either the source or the report-to endpoint is `dtn:none`.
+pub fn check_status_report_policy(
+    bndl: &Bundle,
+    status: StatusInformationPos,
+) -> Result<(), StatusReportError> {
+    if status >= MAX_STATUS_INFORMATION_POS {
+        return Err(StatusReportError::InvalidStatusInformation(status));
+    }
+    if bndl.is_administrative_record() {
+        return Err(StatusReportError::AdministrativeRecord);
+    }
+    if bndl.primary.source == EndpointID::none() {
+        return Err(StatusReportError::AnonymousSource);
+    }
+    if bndl.primary.report_to == EndpointID::none() {
+        return Err(StatusReportError::ReportToNone);
+    }
+    if !is_status_requested(bndl, status) {
+        return Err(StatusReportError::NotRequested(status));
+    }
+    Ok(())
+}
+
 // new_status_report creates a bundle status report for the given bundle and
 // StatusInformationPos, which creates the right bundle status item. The
 // bundle status report reason code will be used and the bundle status item
@@ -365,6 +617,17 @@ pub fn new_status_report(
     bndl: &Bundle,
     status_item: StatusInformationPos,
     reason: StatusReportReason,
+) -> StatusReport {
+    new_multi_status_report(bndl, &[status_item], reason)
+}
+
+/// Creates a bundle status report asserting all given status information entries at once.
+///
+/// No policy checks are done, see [`check_status_report_policy`].
+pub fn new_multi_status_report(
+    bndl: &Bundle,
+    status_items: &[StatusInformationPos],
+    reason: StatusReportReason,
 ) -> StatusReport {
     let mut sr = StatusReport {
         status_information: Vec::new(),
@@ -376,20 +639,21 @@ pub fn new_status_report(
     };
 
     if bndl.primary.has_fragmentation() {
-        // TODO: add frag code
-        unimplemented!();
+        sr.frag_offset = bndl.primary.fragmentation_offset;
+        sr.frag_len = bndl.payload().map_or(0, |p| p.len() as u64);
     }
 
+    let status_time = bndl
+        .primary
+        .bundle_control_flags
+        .contains(BundleControlFlags::BUNDLE_REQUEST_STATUS_TIME);
+    let now = dtn_time_now();
+
     for i in 0..MAX_STATUS_INFORMATION_POS {
-        if i == status_item
-            && bndl
-                .primary
-                .bundle_control_flags
-                .contains(BundleControlFlags::BUNDLE_REQUEST_STATUS_TIME)
-        {
+        if status_items.contains(&i) && status_time {
             sr.status_information
-                .push(new_time_reporting_bundle_status_item(dtn_time_now()));
-        } else if i == status_item {
+                .push(new_time_reporting_bundle_status_item(now));
+        } else if status_items.contains(&i) {
             sr.status_information.push(new_bundle_status_item(true));
         } else {
             sr.status_information.push(new_bundle_status_item(false));
@@ -399,25 +663,475 @@ pub fn new_status_report(
     sr
 }
 
+/// Creates a status report bundle without checking whether a report is permitted.
+///
+/// Use [`status_report_bundle`] to respect the status report requests of the original bundle.
 pub fn new_status_report_bundle(
     orig_bundle: &Bundle,
     src: EndpointID,
     crc_type: crc::CrcRawType,
     status: StatusInformationPos,
     reason: StatusReportReason,
-) -> Bundle {
-    // TODO: implement sanity checks
+) -> Result<Bundle, StatusReportError> {
+    build_status_report_bundle(
+        orig_bundle,
+        src,
+        crc_type,
+        new_status_report(orig_bundle, status, reason),
+    )
+}
+
+/// Lifetime of an administrative record bundle sent in response to `orig`.
+///
+/// Copies the original lifetime, a zero lifetime falls back to [`primary::DEFAULT_LIFETIME`].
+fn record_lifetime(orig: &Bundle) -> core::time::Duration {
+    if orig.primary.lifetime.is_zero() {
+        primary::DEFAULT_LIFETIME
+    } else {
+        orig.primary.lifetime
+    }
+}
+
+/// Creates a status report bundle for all requested assertions in `status_items`.
+///
+/// Assertions the original bundle did not ask for are dropped. An error is returned if
+/// none remain or RFC 9171 forbids reporting on the original bundle at all.
+pub fn status_report_bundle(
+    orig_bundle: &Bundle,
+    src: EndpointID,
+    crc_type: crc::CrcRawType,
+    status_items: &[StatusInformationPos],
+    reason: StatusReportReason,
+) -> Result<Bundle, StatusReportError> {
+    let requested = requested_status_items(orig_bundle, status_items)?;
+    build_status_report_bundle(
+        orig_bundle,
+        src,
+        crc_type,
+        new_multi_status_report(orig_bundle, &requested, reason),
+    )
+}
 
-    let adm_record =
-        AdministrativeRecord::BundleStatusReport(new_status_report(orig_bundle, status, reason));
+/// Filters `status_items` down to the assertions `orig_bundle` asked for, see [`status_report_bundle`].
+pub(crate) fn requested_status_items(
+    orig_bundle: &Bundle,
+    status_items: &[StatusInformationPos],
+) -> Result<Vec<StatusInformationPos>, StatusReportError> {
+    let mut requested: Vec<StatusInformationPos> = Vec::with_capacity(status_items.len());
+    let mut last_err = StatusReportError::NoAssertion;
+    for &status in status_items {
+        match check_status_report_policy(orig_bundle, status) {
+            Ok(()) => requested.push(status),
+            Err(StatusReportError::NotRequested(s)) => {
+                last_err = StatusReportError::NotRequested(s)
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    if requested.is_empty() {
+        return Err(last_err);
+    }
+    Ok(requested)
+}
+
+/// Creates a status report bundle, with `force` all of `status_items` are reported without any policy checks.
+///
+/// Without `force` this is the same as [`status_report_bundle`].
+pub fn status_report_bundle_with(
+    orig_bundle: &Bundle,
+    src: EndpointID,
+    crc_type: crc::CrcRawType,
+    status_items: &[StatusInformationPos],
+    reason: StatusReportReason,
+    force: bool,
+) -> Result<Bundle, StatusReportError> {
+    if !force {
+        return status_report_bundle(orig_bundle, src, crc_type, status_items, reason);
+    }
+    build_status_report_bundle(
+        orig_bundle,
+        src,
+        crc_type,
+        new_multi_status_report(orig_bundle, status_items, reason),
+    )
+}
+
+/// Wraps a status report into a bundle addressed to the report-to endpoint of the original bundle.
+///
+/// No policy checks are done, see [`status_report_bundle`]. Still fails with
+/// [`StatusReportError::ReportToNone`] if the original bundle has no report-to endpoint.
+pub fn build_status_report_bundle(
+    orig_bundle: &Bundle,
+    src: EndpointID,
+    crc_type: crc::CrcRawType,
+    sr: StatusReport,
+) -> Result<Bundle, StatusReportError> {
+    let defaults = RecordDefaults {
+        source: src,
+        lifetime: record_lifetime(orig_bundle),
+    };
+    build_status_report_bundle_with_defaults(orig_bundle, &defaults, crc_type, sr)
+}
+
+/// Source and lifetime of administrative records not sent on behalf of a [`crate::node::NodeContext`].
+struct RecordDefaults {
+    source: EndpointID,
+    lifetime: core::time::Duration,
+}
+
+impl primary::PrimaryDefaults for RecordDefaults {
+    fn source(&self) -> EndpointID {
+        self.source.clone()
+    }
+    fn lifetime(&self) -> core::time::Duration {
+        self.lifetime
+    }
+}
+
+/// Like [`build_status_report_bundle`], taking source, creation timestamp and lifetime from `defaults`.
+pub(crate) fn build_status_report_bundle_with_defaults(
+    orig_bundle: &Bundle,
+    defaults: &impl primary::PrimaryDefaults,
+    crc_type: crc::CrcRawType,
+    sr: StatusReport,
+) -> Result<Bundle, StatusReportError> {
+    let adm_record = AdministrativeRecord::BundleStatusReport(sr);
 
     let pblock = primary::PrimaryBlockBuilder::default()
         .destination(orig_bundle.primary.report_to.clone())
+        .bundle_control_flags(BundleControlFlags::BUNDLE_ADMINISTRATIVE_RECORD_PAYLOAD.bits())
+        .build_with(defaults)?;
+
+    let mut b = bundle::BundleBuilder::default()
+        .primary(pblock)
+        .canonicals(vec![adm_record.to_payload()])
+        .build()
+        .map_err(|err| StatusReportError::InvalidBundle(err.to_string()))?;
+    b.set_crc(crc_type);
+
+    Ok(b)
+}
+
+// Bundle-in-Bundle Encapsulation (BIBE)
+
+/// BibePdu is a BIBE protocol data unit, carrying an encapsulated bundle as an administrative record.
+///
+/// A `transmission_id` of zero indicates that no custody transfer was requested for this BPDU.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BibePdu {
+    pub transmission_id: u64,
+    pub retransmission_time: DtnTime,
+    pub encapsulated_bundle: ByteBuffer,
+}
+
+impl BibePdu {
+    /// Encapsulate the given bundle, custody transfer is requested for non-zero transmission IDs.
+    pub fn new(inner: &mut Bundle, transmission_id: u64, retransmission_time: DtnTime) -> BibePdu {
+        BibePdu {
+            transmission_id,
+            retransmission_time,
+            encapsulated_bundle: inner.to_cbor(),
+        }
+    }
+    /// Extract the BPDU from a bundle carrying it as its administrative record payload.
+    pub fn from_bundle(bndl: &Bundle) -> Result<BibePdu, Error> {
+        match bndl.administrative_record()? {
+            AdministrativeRecord::BibeProtocolDataUnit(bpdu) => Ok(bpdu),
+            _ => Err(Error::BundleError(
+                "Administrative record is not a BIBE protocol data unit".to_string(),
+            )),
+        }
+    }
+    pub fn custody_requested(&self) -> bool {
+        self.transmission_id != 0
+    }
+    /// Decode the encapsulated bundle.
+    pub fn bundle(&self) -> Result<Bundle, Error> {
+        Bundle::try_from(self.encapsulated_bundle.as_slice())
+    }
+}
+
+/// Errors when unpacking a bundle encapsulated with BIBE.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BibeError {
+    #[error(transparent)]
+    AdministrativeRecord(#[from] AdministrativeRecordError),
+    #[error("administrative record of type `{0}` is not a BIBE protocol data unit")]
+    NotBibe(AdministrativeRecordTypeCode),
+    #[error("encapsulated bundle could not be decoded: {0}")]
+    Decode(String),
+    #[error("encapsulated bundle {0} is invalid: {1}")]
+    Invalid(String, String),
+    #[error("encapsulated bundle {0} has exceeded its lifetime")]
+    Expired(String),
+}
+
+impl Serialize for BibePdu {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(3))?;
+        seq.serialize_element(&self.transmission_id)?;
+        seq.serialize_element(&self.retransmission_time)?;
+        seq.serialize_element(&serde_bytes::Bytes::new(&self.encapsulated_bundle))?;
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for BibePdu {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BibePduVisitor;
+
+        impl<'de> Visitor<'de> for BibePduVisitor {
+            type Value = BibePdu;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("BibePdu")
+            }
+
+            fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+            where
+                V: SeqAccess<'de>,
+            {
+                let transmission_id: u64 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let retransmission_time: DtnTime = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                let encapsulated_bundle: ByteBuffer = seq
+                    .next_element::<serde_bytes::ByteBuf>()?
+                    .ok_or_else(|| de::Error::invalid_length(2, &self))?
+                    .into_vec();
+
+                Ok(BibePdu {
+                    transmission_id,
+                    retransmission_time,
+                    encapsulated_bundle,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(BibePduVisitor)
+    }
+}
+
+// Custody Signal
+
+pub type CustodyDisposition = u32;
+
+/// CUSTODY_ACCEPTED signals that custody of the referenced BPDUs has been taken.
+pub const CUSTODY_ACCEPTED: CustodyDisposition = 0;
+
+/// CUSTODY_REDUNDANT signals that the BPDUs were already received before.
+pub const CUSTODY_REDUNDANT: CustodyDisposition = 3;
+
+/// CUSTODY_DEPLETED_STORAGE signals a custody refusal because of depleted storage.
+pub const CUSTODY_DEPLETED_STORAGE: CustodyDisposition = 4;
+
+/// CUSTODY_DEST_ENDPOINT_UNINTELLIGIBLE signals a custody refusal because of an unintelligible destination.
+pub const CUSTODY_DEST_ENDPOINT_UNINTELLIGIBLE: CustodyDisposition = 5;
+
+/// CUSTODY_NO_ROUTE_TO_DESTINATION signals a custody refusal because of no known route to the destination.
+pub const CUSTODY_NO_ROUTE_TO_DESTINATION: CustodyDisposition = 6;
+
+/// CUSTODY_NO_NEXT_NODE_CONTACT signals a custody refusal because of no timely contact with the next node.
+pub const CUSTODY_NO_NEXT_NODE_CONTACT: CustodyDisposition = 7;
+
+/// CUSTODY_BLOCK_UNINTELLIGIBLE signals a custody refusal because of an unintelligible block.
+pub const CUSTODY_BLOCK_UNINTELLIGIBLE: CustodyDisposition = 8;
+
+/// SequenceRange is a run of consecutive transmission IDs, encoded as first ID and run length.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceRange(u64, u64);
+
+impl SequenceRange {
+    pub fn new(start: u64, count: u64) -> SequenceRange {
+        SequenceRange(start, count)
+    }
+    pub fn start(&self) -> u64 {
+        self.0
+    }
+    pub fn count(&self) -> u64 {
+        self.1
+    }
+    pub fn contains(&self, transmission_id: u64) -> bool {
+        transmission_id >= self.0 && transmission_id - self.0 < self.1
+    }
+    /// Last transmission ID of the range, `None` if it is empty or exceeds the ID space.
+    fn last(&self) -> Option<u64> {
+        self.1.checked_sub(1).and_then(|n| self.0.checked_add(n))
+    }
+}
+
+/// Errors for transmission ID ranges of a custody signal.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CustodySignalError {
+    #[error("transmission ID range starting at `{0}` exceeds the ID space")]
+    RangeOverflow(u64),
+    #[error("transmission ID ranges starting at `{0}` and `{1}` overlap")]
+    RangeOverlap(u64, u64),
+}
+
+/// CustodySignal reports the custody disposition for ranges of BPDU transmission IDs.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CustodySignal {
+    pub dispositions: Vec<(CustodyDisposition, Vec<SequenceRange>)>,
+}
+
+impl CustodySignal {
+    pub fn new() -> CustodySignal {
+        Default::default()
+    }
+    /// Add a transmission ID to the given disposition, merging consecutive IDs into ranges.
+    ///
+    /// Fails without changing the signal if a merged range would exceed the ID space.
+    pub fn add(
+        &mut self,
+        disposition: CustodyDisposition,
+        transmission_id: u64,
+    ) -> Result<(), CustodySignalError> {
+        if self.disposition_of(transmission_id).is_some() {
+            return Ok(());
+        }
+        let pos = self.dispositions.iter().position(|d| d.0 == disposition);
+        let mut ranges = pos.map_or_else(Vec::new, |pos| self.dispositions[pos].1.clone());
+        ranges.push(SequenceRange(transmission_id, 1));
+        ranges.sort_by_key(|r| r.0);
+
+        let mut merged: Vec<SequenceRange> = Vec::with_capacity(ranges.len());
+        for r in ranges {
+            if let Some(last) = merged.last_mut()
+                && last.0.checked_add(last.1) == Some(r.0)
+            {
+                last.1 = last
+                    .1
+                    .checked_add(r.1)
+                    .ok_or(CustodySignalError::RangeOverflow(last.0))?;
+            } else {
+                merged.push(r);
+            }
+        }
+        match pos {
+            Some(pos) => self.dispositions[pos].1 = merged,
+            None => self.dispositions.push((disposition, merged)),
+        }
+        Ok(())
+    }
+    /// Check that no range exceeds the ID space and no two ranges overlap.
+    fn check_ranges(&self) -> Result<(), CustodySignalError> {
+        let mut ranges: Vec<&SequenceRange> = self
+            .dispositions
+            .iter()
+            .flat_map(|d| d.1.iter())
+            .filter(|r| r.1 > 0)
+            .collect();
+        ranges.sort_by_key(|r| r.0);
+        let mut prev: Option<(u64, u64)> = None;
+        for r in ranges {
+            let last = r.last().ok_or(CustodySignalError::RangeOverflow(r.0))?;
+            if let Some((start, prev_last)) = prev
+                && prev_last >= r.0
+            {
+                return Err(CustodySignalError::RangeOverlap(start, r.0));
+            }
+            prev = Some((r.0, last));
+        }
+        Ok(())
+    }
+    /// Iterate over all transmission IDs signaled with the given disposition.
+    pub fn transmission_ids(
+        &self,
+        disposition: CustodyDisposition,
+    ) -> impl Iterator<Item = u64> + '_ {
+        self.dispositions
+            .iter()
+            .filter(move |d| d.0 == disposition)
+            .flat_map(|d| d.1.iter())
+            .flat_map(|r| r.0..r.0.saturating_add(r.1))
+    }
+    /// Return the disposition signaled for a transmission ID.
+    pub fn disposition_of(&self, transmission_id: u64) -> Option<CustodyDisposition> {
+        self.dispositions
+            .iter()
+            .find(|d| d.1.iter().any(|r| r.contains(transmission_id)))
+            .map(|d| d.0)
+    }
+}
+
+impl Serialize for CustodySignal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.dispositions.len()))?;
+        for d in &self.dispositions {
+            seq.serialize_element(d)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for CustodySignal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CustodySignalVisitor;
+
+        impl<'de> Visitor<'de> for CustodySignalVisitor {
+            type Value = CustodySignal;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("CustodySignal")
+            }
+
+            fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+            where
+                V: SeqAccess<'de>,
+            {
+                let mut dispositions = Vec::new();
+                while let Some(next) =
+                    seq.next_element::<(CustodyDisposition, Vec<SequenceRange>)>()?
+                {
+                    dispositions.push(next);
+                }
+                let signal = CustodySignal { dispositions };
+                signal.check_ranges().map_err(de::Error::custom)?;
+                Ok(signal)
+            }
+        }
+
+        deserializer.deserialize_any(CustodySignalVisitor)
+    }
+}
+
+/// Create a bundle from `src` to `dst` carrying the given bundle as a BIBE protocol data unit.
+pub fn new_bibe_bundle(
+    inner: &mut Bundle,
+    src: EndpointID,
+    dst: EndpointID,
+    transmission_id: u64,
+    retransmission_time: DtnTime,
+    crc_type: crc::CrcRawType,
+) -> Bundle {
+    let adm_record = AdministrativeRecord::BibeProtocolDataUnit(BibePdu::new(
+        inner,
+        transmission_id,
+        retransmission_time,
+    ));
+
+    let pblock = primary::PrimaryBlockBuilder::default()
+        .destination(dst)
         .source(src.clone())
         .report_to(src)
         .bundle_control_flags(BundleControlFlags::BUNDLE_ADMINISTRATIVE_RECORD_PAYLOAD.bits())
         .creation_timestamp(CreationTimestamp::now())
-        .lifetime(orig_bundle.primary.lifetime)
+        .lifetime(record_lifetime(inner))
         .build()
         .unwrap();
 
@@ -430,3 +1144,243 @@ pub fn new_status_report_bundle(
 
     b
 }
+
+/// Create a custody signal bundle, addressed to the custodian which sent the given BPDU bundle.
+pub fn new_custody_signal_bundle(
+    bpdu_bundle: &Bundle,
+    src: EndpointID,
+    crc_type: crc::CrcRawType,
+    signal: CustodySignal,
+) -> Bundle {
+    let adm_record = AdministrativeRecord::CustodySignal(signal);
+
+    let pblock = primary::PrimaryBlockBuilder::default()
+        .destination(bpdu_bundle.primary.source.clone())
+        .source(src.clone())
+        .report_to(src)
+        .bundle_control_flags(BundleControlFlags::BUNDLE_ADMINISTRATIVE_RECORD_PAYLOAD.bits())
+        .creation_timestamp(CreationTimestamp::now())
+        .lifetime(record_lifetime(bpdu_bundle))
+        .build()
+        .unwrap();
+
+    let mut b = bundle::BundleBuilder::default()
+        .primary(pblock)
+        .canonicals(vec![adm_record.to_payload()])
+        .build()
+        .unwrap();
+    b.set_crc(crc_type);
+
+    b
+}
+
+// Status Report Tracking
+
+/// Lifecycle state of a tracked bundle, derived from the received status reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BundleState {
+    /// No status report received yet
+    #[default]
+    Pending,
+    Received,
+    Forwarded,
+    Delivered,
+    Deleted(StatusReportReason),
+    /// Lifetime exceeded without the bundle being reported as delivered or deleted
+    Expired,
+}
+
+impl BundleState {
+    /// Checks whether no further state changes are expected.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            BundleState::Delivered | BundleState::Deleted(_) | BundleState::Expired
+        )
+    }
+    fn progress(&self) -> u8 {
+        match self {
+            BundleState::Pending => 0,
+            BundleState::Received => 1,
+            BundleState::Forwarded => 2,
+            _ => 3,
+        }
+    }
+}
+
+/// A single status assertion received for a tracked bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusAssertion {
+    pub status: StatusInformationPos,
+    pub reason: StatusReportReason,
+    /// Source of the status report bundle
+    pub reporter: EndpointID,
+    /// Status time, if requested by the original bundle
+    pub time: Option<DtnTime>,
+}
+
+/// Bookkeeping for a bundle registered with the [`StatusReportTracker`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedBundle {
+    pub id: String,
+    pub destination: EndpointID,
+    pub state: BundleState,
+    /// DTN time after which the bundle is considered expired
+    pub expires: DtnTime,
+    pub assertions: Vec<StatusAssertion>,
+}
+
+impl TrackedBundle {
+    fn apply(&mut self, assertion: StatusAssertion) {
+        if !self.state.is_final() {
+            let next = match assertion.status {
+                RECEIVED_BUNDLE => BundleState::Received,
+                FORWARDED_BUNDLE => BundleState::Forwarded,
+                DELIVERED_BUNDLE => BundleState::Delivered,
+                _ => BundleState::Deleted(assertion.reason),
+            };
+            // reports can arrive out of order, only ever move forward
+            if next.is_final() || self.state.progress() < next.progress() {
+                self.state = next;
+            }
+        }
+        self.assertions.push(assertion);
+    }
+    /// Checks whether the given status has been asserted by any status report.
+    pub fn is_asserted(&self, status: StatusInformationPos) -> bool {
+        self.assertions.iter().any(|a| a.status == status)
+    }
+}
+
+/// Tracks outgoing bundles and connects incoming status reports to them.
+///
+/// # Example
+///
+/// ```
+/// use bp7::administrative_record::*;
+/// use bp7::*;
+///
+/// let mut bndl = bundle::new_std_payload_bundle(
+///     "dtn://node1/app".try_into().unwrap(),
+///     "dtn://node2/inbox".try_into().unwrap(),
+///     b"ABC".to_vec(),
+/// );
+/// let mut tracker = StatusReportTracker::new();
+/// tracker.register(&bndl);
+///
+/// let report = status_report_bundle(
+///     &bndl,
+///     "dtn://node2/".try_into().unwrap(),
+///     crc::CRC_NO,
+///     &[DELIVERED_BUNDLE],
+///     NO_INFORMATION,
+/// )
+/// .unwrap();
+/// assert_eq!(tracker.ingest(&report).unwrap(), Some(bndl.id()));
+/// assert_eq!(tracker.state(&bndl.id()), Some(BundleState::Delivered));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StatusReportTracker {
+    bundles: std::collections::HashMap<String, TrackedBundle>,
+}
+
+impl StatusReportTracker {
+    pub fn new() -> StatusReportTracker {
+        Default::default()
+    }
+    /// Register an outgoing bundle, replacing any previous registration with the same ID.
+    pub fn register(&mut self, bndl: &Bundle) {
+        // bundles without creation time are tracked based on their current age
+        let age = u64::try_from(bndl.age().unwrap_or_default().as_millis()).unwrap_or(u64::MAX);
+        let start = dtn_time_now().saturating_sub(age);
+        let lifetime = u64::try_from(bndl.primary.lifetime.as_millis()).unwrap_or(u64::MAX);
+        let tracked = TrackedBundle {
+            id: bndl.id(),
+            destination: bndl.primary.destination.clone(),
+            state: BundleState::Pending,
+            expires: start.saturating_add(lifetime),
+            assertions: Vec::new(),
+        };
+        self.bundles.insert(tracked.id.clone(), tracked);
+    }
+    /// Ingest a status report bundle.
+    ///
+    /// Returns the ID of the tracked bundle the report refers to, or `None` if the
+    /// referenced bundle is not tracked or the record is not a status report.
+    pub fn ingest(&mut self, report: &Bundle) -> Result<Option<String>, AdministrativeRecordError> {
+        if let AdministrativeRecord::BundleStatusReport(sr) = report.administrative_record()? {
+            Ok(self.ingest_report(&report.primary.source, &sr))
+        } else {
+            Ok(None)
+        }
+    }
+    /// Ingest an already decoded status report, sent by `reporter`.
+    pub fn ingest_report(&mut self, reporter: &EndpointID, sr: &StatusReport) -> Option<String> {
+        let mut id = sr.refbundle();
+        if !self.bundles.contains_key(&id) && sr.frag_len > 0 {
+            // reports for fragments are accounted to the original bundle
+            id = format!(
+                "{}-{}-{}",
+                sr.source_node,
+                sr.timestamp.dtntime(),
+                sr.timestamp.seqno()
+            );
+        }
+        let tracked = self.bundles.get_mut(&id)?;
+        for (status, item) in sr.status_information.iter().enumerate() {
+            if item.asserted {
+                tracked.apply(StatusAssertion {
+                    status: status as StatusInformationPos,
+                    reason: sr.report_reason,
+                    reporter: reporter.clone(),
+                    time: item.status_requested.then_some(item.time),
+                });
+            }
+        }
+        Some(id)
+    }
+    /// Mark all bundles as expired whose lifetime ended before `now` without being
+    /// reported as delivered or deleted. Returns the IDs of newly expired bundles.
+    pub fn expire(&mut self, now: DtnTime) -> Vec<String> {
+        let mut expired = Vec::new();
+        for tracked in self.bundles.values_mut() {
+            if !tracked.state.is_final() && tracked.expires <= now {
+                tracked.state = BundleState::Expired;
+                expired.push(tracked.id.clone());
+            }
+        }
+        expired
+    }
+    pub fn get(&self, id: &str) -> Option<&TrackedBundle> {
+        self.bundles.get(id)
+    }
+    pub fn state(&self, id: &str) -> Option<BundleState> {
+        self.bundles.get(id).map(|t| t.state)
+    }
+    /// Stop tracking a bundle.
+    pub fn remove(&mut self, id: &str) -> Option<TrackedBundle> {
+        self.bundles.remove(id)
+    }
+    /// Remove all bundles in a final state and return them.
+    pub fn drain_finished(&mut self) -> Vec<TrackedBundle> {
+        let finished: Vec<String> = self
+            .bundles
+            .values()
+            .filter(|t| t.state.is_final())
+            .map(|t| t.id.clone())
+            .collect();
+        finished
+            .iter()
+            .filter_map(|id| self.bundles.remove(id))
+            .collect()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &TrackedBundle> {
+        self.bundles.values()
+    }
+    pub fn len(&self) -> usize {
+        self.bundles.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+}
@@ -1,10 +1,14 @@
 use core::cmp;
 use core::convert::TryFrom;
 use core::fmt;
+use core::time::Duration;
 use serde::de::{SeqAccess, Visitor};
 use serde::ser::{SerializeSeq, Serializer};
 use serde::{Deserialize, Deserializer, Serialize, de};
 
+use super::administrative_record::{
+    AdministrativeRecord, AdministrativeRecordError, BibeError, new_bibe_bundle,
+};
 use super::canonical::*;
 use super::crc::*;
 use super::dtntime::*;
@@ -12,6 +16,7 @@ use super::eid::*;
 use super::flags::*;
 use super::primary::*;
 use crate::error::{Error, ErrorList};
+use crate::json::JsonBundle;
 use thiserror::Error;
 
 /// Version for upcoming bundle protocol standard is 7.
@@ -47,6 +52,12 @@ pub enum BundleBuilderError {
     NoPayloadBlock,
 }
 
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BlockError {
+    #[error("bundle already contains a block of type `{0}`")]
+    DuplicateBlockType(CanonicalBlockType),
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct BundleBuilder {
     primary: PrimaryBlock,
@@ -72,7 +83,7 @@ impl BundleBuilder {
     }
     pub fn build(mut self) -> Result<Bundle, BundleBuilderError> {
         self.canonicals
-            .sort_by(|a, b| b.block_number.cmp(&a.block_number));
+            .sort_by_key(|b| core::cmp::Reverse(b.block_number));
 
         if self.canonicals.is_empty() || self.canonicals.last().unwrap().payload_data().is_none() {
             Err(BundleBuilderError::NoPayloadBlock)
@@ -222,7 +233,7 @@ impl Bundle {
     /// Sort canonical blocks by block number
     pub fn sort_canonicals(&mut self) {
         self.canonicals
-            .sort_by(|a, b| b.block_number.cmp(&a.block_number));
+            .sort_by_key(|b| core::cmp::Reverse(b.block_number));
     }
     fn next_canonical_block_number(&self) -> u64 {
         let mut highest_block_number = 1;
@@ -233,24 +244,71 @@ impl Bundle {
     }
 
     /// Automatically assign a block number and add canonical block to bundle
-    pub fn add_canonical_block(&mut self, mut cblock: CanonicalBlock) {
-        // TODO: report errors
-        if (cblock.block_type == PAYLOAD_BLOCK
-            || cblock.block_type == HOP_COUNT_BLOCK
-            || cblock.block_type == BUNDLE_AGE_BLOCK
-            || cblock.block_type == PREVIOUS_NODE_BLOCK)
-            && self.extension_block_by_type(cblock.block_type).is_some()
+    ///
+    /// Blocks of a type that may only occur once and is already present are ignored,
+    /// see [`Bundle::insert_block`] for a variant reporting this.
+    pub fn add_canonical_block(&mut self, cblock: CanonicalBlock) {
+        let _ = self.insert_block(cblock);
+    }
+    /// Insert a canonical block, returning the block number assigned to it.
+    ///
+    /// The payload block always becomes block 1, all others get the next free number.
+    /// Payload, hop count, bundle age and previous node blocks may only occur once.
+    pub fn insert_block(
+        &mut self,
+        mut cblock: CanonicalBlock,
+    ) -> Result<CanonicalBlockNumberType, BlockError> {
+        if matches!(
+            cblock.block_type,
+            PAYLOAD_BLOCK | HOP_COUNT_BLOCK | BUNDLE_AGE_BLOCK | PREVIOUS_NODE_BLOCK
+        ) && self
+            .canonicals
+            .iter()
+            .any(|b| b.block_type == cblock.block_type)
         {
-            return;
+            return Err(BlockError::DuplicateBlockType(cblock.block_type));
         }
-        let block_num = if cblock.block_type == PAYLOAD_BLOCK {
+        cblock.block_number = if cblock.block_type == PAYLOAD_BLOCK {
             crate::canonical::PAYLOAD_BLOCK_NUMBER
         } else {
             self.next_canonical_block_number()
         };
-        cblock.block_number = block_num;
+        let block_number = cblock.block_number;
         self.canonicals.push(cblock);
         self.sort_canonicals();
+        Ok(block_number)
+    }
+    /// Insert a canonical block using the CRC type of the primary block and update all checksums.
+    ///
+    /// Fails for duplicate blocks just like [`Bundle::insert_block`].
+    pub fn add_block(
+        &mut self,
+        mut cblock: CanonicalBlock,
+    ) -> Result<CanonicalBlockNumberType, BlockError> {
+        cblock.set_crc_type(self.primary.crc_type());
+        let block_number = self.insert_block(cblock)?;
+        self.calculate_crc();
+        Ok(block_number)
+    }
+    /// Get the canonical block with the given block number.
+    pub fn block(&self, block_number: CanonicalBlockNumberType) -> Option<&CanonicalBlock> {
+        self.canonicals
+            .iter()
+            .find(|b| b.block_number == block_number)
+    }
+    /// Get the canonical block with the given block number for modification.
+    ///
+    /// Its CRC is recalculated on the next encoding. Block number and type must not be changed.
+    pub fn block_mut(
+        &mut self,
+        block_number: CanonicalBlockNumberType,
+    ) -> Option<&mut CanonicalBlock> {
+        let block = self
+            .canonicals
+            .iter_mut()
+            .find(|b| b.block_number == block_number)?;
+        block.received.invalidate();
+        Some(block)
     }
     /// Checks whether the bundle is an administrative record
     pub fn is_administrative_record(&self) -> bool {
@@ -259,6 +317,91 @@ impl Bundle {
             .flags()
             .contains(BundleControlFlags::BUNDLE_ADMINISTRATIVE_RECORD_PAYLOAD)
     }
+    /// Decode the payload as administrative record, if the bundle is flagged as one.
+    ///
+    /// Payloads not decoding as administrative record as well as records with a known
+    /// type but malformed content are reported as errors.
+    pub fn administrative_record(&self) -> Result<AdministrativeRecord, AdministrativeRecordError> {
+        if !self.is_administrative_record() {
+            return Err(AdministrativeRecordError::NotAdministrativeRecord);
+        }
+        let payload = self
+            .payload()
+            .ok_or(AdministrativeRecordError::MissingPayload)?;
+        let record = AdministrativeRecord::from_cbor(payload)
+            .map_err(|err| AdministrativeRecordError::Mismatched(err.to_string()))?;
+        match record {
+            AdministrativeRecord::Mismatched(
+                crate::administrative_record::BUNDLE_STATUS_REPORT_TYPE_CODE,
+                _,
+            ) => Err(AdministrativeRecordError::MalformedStatusReport),
+            AdministrativeRecord::Mismatched(code, _) => {
+                Err(AdministrativeRecordError::Malformed(code))
+            }
+            _ => Ok(record),
+        }
+    }
+    /// Check whether the administrative record flag matches the payload.
+    ///
+    /// In contrast to [`Bundle::administrative_record`], payloads of bundles not flagged as
+    /// administrative record are also inspected and reported if they decode as a known record.
+    pub fn check_administrative_record(&self) -> Result<(), AdministrativeRecordError> {
+        if self.is_administrative_record() {
+            return self.administrative_record().map(|_| ());
+        }
+        if let Some(payload) = self.payload()
+            && let Ok(record) = AdministrativeRecord::from_cbor(payload)
+            && !matches!(
+                record,
+                AdministrativeRecord::Unknown(_, _) | AdministrativeRecord::Mismatched(_, _)
+            )
+        {
+            return Err(AdministrativeRecordError::Unflagged(record.type_code()));
+        }
+        Ok(())
+    }
+    /// Encapsulate this bundle in a new BIBE bundle from `outer_src` to `outer_dst`.
+    ///
+    /// No custody transfer is requested and the outer bundle lives no longer than the
+    /// remaining lifetime of this bundle, see [`new_bibe_bundle`] for the general case.
+    /// Bundles that have exceeded their lifetime are not encapsulated.
+    pub fn encapsulate(
+        &mut self,
+        outer_src: EndpointID,
+        outer_dst: EndpointID,
+        crc_type: CrcRawType,
+    ) -> Result<Bundle, BibeError> {
+        let remaining = self.remaining_lifetime();
+        if remaining == Some(Duration::ZERO) {
+            return Err(BibeError::Expired(self.id()));
+        }
+        let mut outer = new_bibe_bundle(self, outer_src, outer_dst, 0, 0, crc_type);
+        if let Some(remaining) = remaining {
+            outer.primary.lifetime = remaining;
+        }
+        outer.calculate_crc();
+        Ok(outer)
+    }
+    /// Unpack the bundle carried by this BIBE bundle.
+    ///
+    /// The encapsulated bundle must be valid and within its lifetime.
+    pub fn decapsulate(&self) -> Result<Bundle, BibeError> {
+        let bpdu = match self.administrative_record()? {
+            AdministrativeRecord::BibeProtocolDataUnit(bpdu) => bpdu,
+            record => return Err(BibeError::NotBibe(record.type_code())),
+        };
+        let inner = bpdu
+            .bundle()
+            .map_err(|err| BibeError::Decode(err.to_string()))?;
+        if let Err(errs) = inner.validate() {
+            let errs: Vec<String> = errs.iter().map(|e| e.to_string()).collect();
+            return Err(BibeError::Invalid(inner.id(), errs.join(", ")));
+        }
+        if inner.is_lifetime_exceeded() {
+            return Err(BibeError::Expired(inner.id()));
+        }
+        Ok(inner)
+    }
     /// Return payload of bundle if an payload block exists and carries data.
     pub fn payload(&self) -> Option<&ByteBuffer> {
         self.extension_block_by_type(crate::canonical::PAYLOAD_BLOCK)?
@@ -282,6 +425,79 @@ impl Bundle {
             self.set_payload_block(new_payload);
         }
     }
+    /// Replace the payload, returning the previous one.
+    ///
+    /// Flags and CRC type of an existing payload block are kept, a missing payload
+    /// block is added with the CRC type of the primary block.
+    pub fn replace_payload(&mut self, payload: ByteBuffer) -> Option<ByteBuffer> {
+        match self
+            .canonicals
+            .iter_mut()
+            .find(|b| b.block_type == PAYLOAD_BLOCK)
+        {
+            Some(pb) => {
+                let old = pb.payload_data().cloned();
+                pb.set_data(CanonicalData::Data(payload));
+                old
+            }
+            None => {
+                let mut pb = new_payload_block(BlockControlFlags::empty(), payload);
+                pb.set_crc_type(self.primary.crc_type());
+                self.add_canonical_block(pb);
+                None
+            }
+        }
+    }
+    /// Removes the extension block with the given block number.
+    ///
+    /// The payload block can not be removed, `None` is returned instead.
+    /// With the `bpsec` feature, the block is also dropped from the targets of
+    /// integrity blocks, removing those left without targets.
+    pub fn remove_block(
+        &mut self,
+        block_number: CanonicalBlockNumberType,
+    ) -> Option<CanonicalBlock> {
+        let pos = self
+            .canonicals
+            .iter()
+            .position(|b| b.block_number == block_number && b.block_type != PAYLOAD_BLOCK)?;
+        let removed = self.canonicals.remove(pos);
+        #[cfg(feature = "bpsec")]
+        self.remove_security_target(block_number);
+        Some(removed)
+    }
+    #[cfg(feature = "bpsec")]
+    fn remove_security_target(&mut self, block_number: CanonicalBlockNumberType) {
+        use crate::security::{INTEGRITY_BLOCK, IntegrityBlock};
+
+        let mut emptied = Vec::new();
+        for b in self
+            .canonicals
+            .iter_mut()
+            .filter(|b| b.block_type == INTEGRITY_BLOCK)
+        {
+            let CanonicalData::Unknown(data) = b.data() else {
+                continue;
+            };
+            let Ok(mut bib) = IntegrityBlock::from_cbor(data) else {
+                continue;
+            };
+            let Some(pos) = bib.security_targets.iter().position(|t| *t == block_number) else {
+                continue;
+            };
+            bib.security_targets.remove(pos);
+            if pos < bib.security_results.len() {
+                bib.security_results.remove(pos);
+            }
+            if bib.security_targets.is_empty() {
+                emptied.push(b.block_number);
+            } else {
+                b.set_data(CanonicalData::Unknown(bib.to_cbor()));
+            }
+        }
+        self.canonicals
+            .retain(|b| !emptied.contains(&b.block_number));
+    }
     /// Sets the given CRCType for each block. The crc value
     /// is calculated on-the-fly before serializing.
     pub fn set_crc(&mut self, crc_type: CrcRawType) {
@@ -302,11 +518,36 @@ impl Bundle {
         }
         true
     }
+    /// Check the CRC of each block, starting with the primary block.
+    ///
+    /// Blocks decoded from CBOR are checked against the bytes they were received as.
+    pub fn crc_report(&self) -> Vec<BlockCrcReport> {
+        let primary = BlockCrcReport {
+            block_number: 0,
+            block_type: None,
+            crc_type: self.primary.crc_type(),
+            span: self.primary.received.span.clone(),
+            status: crc_status(&self.primary),
+        };
+        core::iter::once(primary)
+            .chain(self.canonicals.iter().map(|b| BlockCrcReport {
+                block_number: b.block_number,
+                block_type: Some(b.block_type),
+                crc_type: b.crc_type(),
+                span: b.received.span.clone(),
+                status: crc_status(b),
+            }))
+            .collect()
+    }
     /// Calculate crc for all blocks.
     pub fn calculate_crc(&mut self) {
-        self.primary.update_crc();
+        if self.primary.received_raw().is_none() {
+            self.primary.update_crc();
+        }
         for b in &mut self.canonicals {
-            b.update_crc();
+            if b.received_raw().is_none() {
+                b.update_crc();
+            }
         }
     }
 
@@ -330,18 +571,135 @@ impl Bundle {
     }
 
     /// Serialize bundle as CBOR encoded byte buffer.
+    ///
+    /// CRCs are calculated while encoding, each block is only serialized once.
+    /// Unchanged blocks of a bundle decoded by [`Bundle::from_cbor_preserving`]
+    /// are written as received.
     pub fn to_cbor(&mut self) -> ByteBuffer {
-        self.calculate_crc();
-        let mut bytebuf = serde_cbor::to_vec(&self).expect("Error serializing bundle as cbor.");
-        bytebuf[0] = 0x9f; // TODO: fix hack, indefinite-length array encoding
-        bytebuf.push(0xff); // break mark
+        let header = self.preserved_array_header().map(<[u8]>::to_vec);
+        let definite = header.is_some();
+        // indefinite-length array encoding by default
+        let mut bytebuf = header.unwrap_or_else(|| vec![0x9f]);
+        match self.primary.received_raw() {
+            Some(raw) => bytebuf.extend_from_slice(raw),
+            None => write_with_crc(&mut self.primary, &mut bytebuf),
+        }
+        for b in &mut self.canonicals {
+            match b.received_raw() {
+                Some(raw) => bytebuf.extend_from_slice(raw),
+                None => write_with_crc(b, &mut bytebuf),
+            }
+        }
+        if !definite {
+            bytebuf.push(0xff); // break mark
+        }
         bytebuf
     }
 
-    /// Serialize bundle as JSON encoded string.
+    /// Exact length of the encoding returned by [`Bundle::to_cbor`], without encoding the bundle.
+    pub fn encoded_len(&self) -> usize {
+        let header = match self.preserved_array_header() {
+            Some(header) => header.len(),
+            None => 2, // indefinite-length array and break mark
+        };
+        let primary = match self.primary.received_raw() {
+            Some(raw) => raw.len(),
+            None => self.primary.encoded_len(),
+        };
+        let canonicals: usize = self
+            .canonicals
+            .iter()
+            .map(|b| match b.received_raw() {
+                Some(raw) => raw.len(),
+                None => b.encoded_len(),
+            })
+            .sum();
+        header + primary + canonicals
+    }
+
+    /// Received definite-length array header, as long as it matches the number of blocks.
+    fn preserved_array_header(&self) -> Option<&[u8]> {
+        let blocks = self.canonicals.len() as u64 + 1;
+        self.primary
+            .received
+            .array_header
+            .as_deref()
+            .filter(|h| matches!(cbor_array_header(h), Ok((Some(len), _)) if len == blocks))
+    }
+
+    /// Decode a CBOR encoded bundle, keeping the received bytes of each block.
+    ///
+    /// Blocks not changed afterwards are written back verbatim by [`Bundle::to_cbor`],
+    /// leaving their CRCs and any signatures over them intact.
+    pub fn from_cbor_preserving(data: &[u8]) -> Result<Bundle, Error> {
+        Bundle::from_cbor_slice(data, true)
+    }
+
+    /// Decode a CBOR encoded bundle from the start of `data`, returning it together
+    /// with the number of bytes consumed.
+    ///
+    /// Blocks are decoded one by one to compute their CRCs over the received bytes.
+    fn decode_cbor(data: &[u8], preserve: bool) -> Result<(Bundle, usize), serde_cbor::Error> {
+        let (mut remaining, mut pos) = cbor_array_header(data)?;
+        let header = &data[..pos];
+        let mut primary: Option<PrimaryBlock> = None;
+        let mut canonicals: Vec<CanonicalBlock> = Vec::new();
+        loop {
+            match remaining {
+                Some(0) => break,
+                Some(n) => remaining = Some(n - 1),
+                None if data.get(pos) == Some(&0xff) => {
+                    pos += 1; // break mark
+                    break;
+                }
+                None => {}
+            }
+            let mut de = serde_cbor::Deserializer::from_slice(&data[pos..]);
+            if primary.is_none() {
+                let mut pb = PrimaryBlock::deserialize(&mut de)?;
+                let end = pos + de.byte_offset();
+                pb.received =
+                    ReceivedEncoding::new(&pb, Box::new(pb.clone()), &data[pos..end], pos..end);
+                if preserve {
+                    pb.received.raw = Some(data[pos..end].to_vec());
+                    pb.received.array_header = Some(header.to_vec());
+                }
+                primary = Some(pb);
+                pos = end;
+            } else {
+                let mut cb = CanonicalBlock::deserialize(&mut de)?;
+                let end = pos + de.byte_offset();
+                cb.received = ReceivedEncoding::new(&cb, cb.fields(), &data[pos..end], pos..end);
+                if preserve {
+                    cb.received.raw = Some(data[pos..end].to_vec());
+                }
+                canonicals.push(cb);
+                pos = end;
+            }
+        }
+        let primary = primary.ok_or_else(|| cbor_error("bundle without primary block"))?;
+        Ok((
+            Bundle {
+                primary,
+                canonicals,
+            },
+            pos,
+        ))
+    }
+
+    /// Decode exactly one CBOR encoded bundle.
+    fn from_cbor_slice(data: &[u8], preserve: bool) -> Result<Bundle, Error> {
+        let (bndl, len) = Bundle::decode_cbor(data, preserve)?;
+        if len != data.len() {
+            return Err(cbor_error("trailing data after bundle").into());
+        }
+        Ok(bndl)
+    }
+
+    /// Serialize bundle as human-readable JSON, see [`crate::json`].
     pub fn to_json(&mut self) -> String {
         self.calculate_crc();
-        serde_json::to_string(&self).unwrap()
+        serde_json::to_string(&JsonBundle::from(&*self)).unwrap()
     }
 
     /// ID returns a kind of uniquene representation of this bundle, containing
@@ -365,9 +723,12 @@ impl Bundle {
     }
 
     /// Update extension blocks such as hop count, bundle age and previous node.
+    ///
+    /// The residence time at the local node is added to the bundle age. If the creation
+    /// time is zero and no bundle age block is present yet, one is added.
     /// Return true if all successful, omit missing blocks.
     /// Return false if hop count is exceeded, bundle age exceeds life time or bundle lifetime itself is exceeded
-    pub fn update_extensions(&mut self, local_node: EndpointID, residence_time: u128) -> bool {
+    pub fn update_extensions(&mut self, local_node: EndpointID, residence_time: Duration) -> bool {
         if let Some(hcblock) = self.extension_block_by_type_mut(HOP_COUNT_BLOCK) {
             hcblock.hop_count_increase();
             if hcblock.hop_count_exceeded() {
@@ -377,16 +738,55 @@ impl Bundle {
         if let Some(pnblock) = self.extension_block_by_type_mut(PREVIOUS_NODE_BLOCK) {
             pnblock.previous_node_update(local_node);
         }
+        self.ensure_bundle_age_block();
         if let Some(bablock) = self.extension_block_by_type_mut(BUNDLE_AGE_BLOCK)
-            && let Some(ba_orig) = bablock.bundle_age_get()
+            && let Some(ba_orig) = bablock.bundle_age()
         {
-            bablock.bundle_age_update(ba_orig + residence_time);
-            if ba_orig + residence_time > self.primary.lifetime.as_micros() {
-                // TODO: check lifetime exceeded calculations with rfc
-                return false;
-            }
+            bablock.set_bundle_age(ba_orig.saturating_add(residence_time));
         }
-        !self.primary.is_lifetime_exceeded()
+        !self.is_lifetime_exceeded()
+    }
+
+    /// Add a bundle age block if the creation time is zero and none is present yet.
+    ///
+    /// Return true if a block was added.
+    pub fn ensure_bundle_age_block(&mut self) -> bool {
+        if self.primary.creation_timestamp.dtntime() != 0
+            || self.extension_block_by_type(BUNDLE_AGE_BLOCK).is_some()
+        {
+            return false;
+        }
+        let mut bablock = new_bundle_age_block(0, BlockControlFlags::empty(), 0);
+        bablock.set_crc_type(self.primary.crc_type());
+        self.add_canonical_block(bablock);
+        true
+    }
+
+    /// Age of the bundle.
+    ///
+    /// Derived from the creation timestamp if the source has a clock, otherwise taken
+    /// from the bundle age block. `None` if neither is available.
+    pub fn age(&self) -> Option<Duration> {
+        let created = self.primary.creation_timestamp.dtntime();
+        if created != 0 {
+            Some(Duration::from_millis(
+                dtn_time_now().saturating_sub(created),
+            ))
+        } else {
+            self.extension_block_by_type(BUNDLE_AGE_BLOCK)?.bundle_age()
+        }
+    }
+
+    /// Remaining lifetime of the bundle, zero if already expired.
+    ///
+    /// `None` if the age of the bundle can not be determined, see [`Bundle::age`].
+    pub fn remaining_lifetime(&self) -> Option<Duration> {
+        Some(self.primary.lifetime.saturating_sub(self.age()?))
+    }
+
+    /// Checks whether the lifetime of the bundle is exceeded, based on creation time or bundle age.
+    pub fn is_lifetime_exceeded(&self) -> bool {
+        self.remaining_lifetime() == Some(Duration::ZERO)
     }
 
     /// Return the previous node of a bundle should a Previous Node Block exist
@@ -394,6 +794,48 @@ impl Bundle {
         let pnblock = self.extension_block_by_type(PREVIOUS_NODE_BLOCK)?;
         pnblock.previous_node_get()
     }
+
+    /// Overview of the bundle for logging and user interfaces.
+    pub fn summary(&self) -> BundleSummary {
+        let primary = &self.primary;
+        BundleSummary {
+            id: self.id(),
+            source: primary.source.clone(),
+            destination: primary.destination.clone(),
+            report_to: primary.report_to.clone(),
+            creation_timestamp: primary.creation_timestamp.clone(),
+            lifetime: primary.lifetime,
+            remaining_lifetime: self.remaining_lifetime(),
+            hop_count: self
+                .extension_block_by_type(HOP_COUNT_BLOCK)
+                .and_then(|b| b.hop_count_get()),
+            flags: primary.bundle_control_flags,
+            payload_len: self.payload().map_or(0, |p| p.len()),
+            blocks: self
+                .canonicals
+                .iter()
+                .map(|b| BlockSummary {
+                    number: b.block_number,
+                    block_type: b.block_type,
+                    type_name: block_type_name(b.block_type),
+                    flags: b.block_control_flags,
+                    crc_type: b.crc_type(),
+                })
+                .collect(),
+            administrative_record: self.is_administrative_record().then(|| {
+                match self.administrative_record() {
+                    Ok(record) => record.to_string(),
+                    Err(err) => err.to_string(),
+                }
+            }),
+            invalid_crc_blocks: self
+                .crc_report()
+                .iter()
+                .filter(|r| !r.is_valid())
+                .map(|r| r.block_number)
+                .collect(),
+        }
+    }
 }
 
 impl fmt::Display for Bundle {
@@ -402,15 +844,144 @@ impl fmt::Display for Bundle {
     }
 }
 
+/// Overview of a bundle, see [`Bundle::summary`].
+///
+/// Displayed as a single line, the alternate form `{:#}` prints one field per line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleSummary {
+    pub id: String,
+    pub source: EndpointID,
+    pub destination: EndpointID,
+    pub report_to: EndpointID,
+    pub creation_timestamp: CreationTimestamp,
+    pub lifetime: Duration,
+    /// `None` if the age of the bundle is unknown.
+    pub remaining_lifetime: Option<Duration>,
+    /// Hop limit and count, if a hop count block is present.
+    pub hop_count: Option<(u8, u8)>,
+    pub flags: BundleControlFlagsType,
+    pub payload_len: usize,
+    pub blocks: Vec<BlockSummary>,
+    /// Description of the administrative record, or why it could not be decoded.
+    pub administrative_record: Option<String>,
+    /// Blocks failing their CRC check, 0 being the primary block.
+    pub invalid_crc_blocks: Vec<CanonicalBlockNumberType>,
+}
+
+/// Canonical block as part of a [`BundleSummary`], displayed as `#2 hop_count (10, crc 1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockSummary {
+    pub number: CanonicalBlockNumberType,
+    pub block_type: CanonicalBlockType,
+    pub type_name: Option<&'static str>,
+    pub flags: BlockControlFlagsType,
+    pub crc_type: CrcRawType,
+}
+
+impl BundleSummary {
+    /// Remaining lifetime in whole seconds, `expired` or `unknown age`.
+    pub fn lifetime_status(&self) -> String {
+        match self.remaining_lifetime {
+            Some(d) if d.is_zero() => "expired".to_string(),
+            Some(d) => format!(
+                "{} left",
+                humantime::format_duration(Duration::from_secs(d.as_secs()))
+            ),
+            None => "unknown age".to_string(),
+        }
+    }
+    fn crc_status(&self) -> String {
+        if self.invalid_crc_blocks.is_empty() {
+            return "valid".to_string();
+        }
+        let invalid: Vec<String> = self
+            .invalid_crc_blocks
+            .iter()
+            .map(|n| format!("#{}", n))
+            .collect();
+        format!("invalid ({})", invalid.join(", "))
+    }
+}
+
+impl fmt::Display for BlockSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} ({}, crc {})",
+            self.number,
+            self.type_name.unwrap_or("unknown"),
+            self.block_type,
+            self.crc_type
+        )
+    }
+}
+
+impl fmt::Display for BundleSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let flags = BundleControlFlags::from_bits_retain(self.flags);
+        if !f.alternate() {
+            let blocks: Vec<&str> = self
+                .blocks
+                .iter()
+                .map(|b| b.type_name.unwrap_or("unknown"))
+                .collect();
+            write!(
+                f,
+                "{} -> {}, {} bytes, {}",
+                self.id,
+                self.destination,
+                self.payload_len,
+                self.lifetime_status()
+            )?;
+            if let Some((limit, count)) = self.hop_count {
+                write!(f, ", hops {}/{}", count, limit)?;
+            }
+            if !flags.is_empty() {
+                write!(f, ", flags {}", flags)?;
+            }
+            write!(f, ", blocks [{}]", blocks.join(", "))?;
+            if let Some(record) = &self.administrative_record {
+                write!(f, ", {}", record)?;
+            }
+            if !self.invalid_crc_blocks.is_empty() {
+                write!(f, ", crc {}", self.crc_status())?;
+            }
+            return Ok(());
+        }
+
+        let blocks: Vec<String> = self.blocks.iter().map(|b| b.to_string()).collect();
+        writeln!(f, "bundle:   {}", self.id)?;
+        writeln!(
+            f,
+            "route:    {} -> {} (report to {})",
+            self.source, self.destination, self.report_to
+        )?;
+        writeln!(f, "created:  {}", self.creation_timestamp)?;
+        writeln!(
+            f,
+            "lifetime: {} ({})",
+            humantime::format_duration(self.lifetime),
+            self.lifetime_status()
+        )?;
+        if let Some((limit, count)) = self.hop_count {
+            writeln!(f, "hops:     {}/{}", count, limit)?;
+        }
+        writeln!(f, "flags:    {}", flags)?;
+        writeln!(f, "blocks:   {}", blocks.join(", "))?;
+        writeln!(f, "payload:  {} bytes", self.payload_len)?;
+        if let Some(record) = &self.administrative_record {
+            writeln!(f, "record:   {}", record)?;
+        }
+        write!(f, "crc:      {}", self.crc_status())
+    }
+}
+
 /// Deserialize from CBOR byte buffer.
 impl TryFrom<ByteBuffer> for Bundle {
     type Error = Error;
 
     fn try_from(item: ByteBuffer) -> Result<Self, Self::Error> {
-        match serde_cbor::from_slice(&item) {
-            Ok(bndl) => Ok(bndl),
-            Err(err) => Err(err.into()),
-        }
+        Bundle::from_cbor_slice(&item, false)
     }
 }
 
@@ -419,25 +990,93 @@ impl TryFrom<&[u8]> for Bundle {
     type Error = Error;
 
     fn try_from(item: &[u8]) -> Result<Self, Self::Error> {
-        match serde_cbor::from_slice(item) {
-            Ok(bndl) => Ok(bndl),
-            Err(err) => Err(err.into()),
-        }
+        Bundle::from_cbor_slice(item, false)
     }
 }
 
-/// Deserialize from JSON string.
+/// Deserialize from the JSON representation produced by [`Bundle::to_json`].
 impl TryFrom<String> for Bundle {
     type Error = Error;
 
     fn try_from(item: String) -> Result<Self, Self::Error> {
-        match serde_json::from_str(&item) {
-            Ok(bndl) => Ok(bndl),
-            Err(err) => Err(err.into()),
+        let json: JsonBundle = serde_json::from_str(&item)?;
+        Bundle::try_from(&json)
+    }
+}
+
+/// Iterator over CBOR encoded bundles written back to back, e.g., in a bundle store.
+///
+/// Yields each bundle together with its raw encoding. Iteration stops after the
+/// first decoding error as the start of the next bundle can not be determined.
+#[derive(Debug, Clone)]
+pub struct BundleStream<'a> {
+    buf: &'a [u8],
+    offset: usize,
+    failed: bool,
+}
+
+impl<'a> BundleStream<'a> {
+    pub fn new(buf: &'a [u8]) -> BundleStream<'a> {
+        BundleStream {
+            buf,
+            offset: 0,
+            failed: false,
+        }
+    }
+    /// Byte offset of the next bundle within the stream.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for BundleStream<'a> {
+    type Item = Result<(Bundle, &'a [u8]), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset >= self.buf.len() {
+            return None;
+        }
+        let remaining = &self.buf[self.offset..];
+        match Bundle::decode_cbor(remaining, false) {
+            Ok((bndl, len)) => {
+                let raw = &remaining[..len];
+                self.offset += len;
+                Some(Ok((bndl, raw)))
+            }
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err.into()))
+            }
         }
     }
 }
 
+/// Parse the header of a CBOR array, returning its length, `None` for
+/// indefinite-length arrays, and the size of the header.
+pub(crate) fn cbor_array_header(data: &[u8]) -> Result<(Option<u64>, usize), serde_cbor::Error> {
+    let first = *data
+        .first()
+        .ok_or_else(|| cbor_error("empty input, expected bundle"))?;
+    match first {
+        0x9f => Ok((None, 1)),
+        0x80..=0x97 => Ok((Some(u64::from(first - 0x80)), 1)),
+        0x98..=0x9b => {
+            let size = 1 << (first - 0x98);
+            let len = data
+                .get(1..1 + size)
+                .ok_or_else(|| cbor_error("truncated bundle array header"))?
+                .iter()
+                .fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+            Ok((Some(len), 1 + size))
+        }
+        _ => Err(cbor_error("expected bundle as CBOR array")),
+    }
+}
+
+fn cbor_error(msg: &str) -> serde_cbor::Error {
+    de::Error::custom(msg)
+}
+
 /// Creates a new bundle with the given endpoints, a hop count block
 ///  and a payload block.
 /// CRC is set to CrcNo by default and the lifetime is set to 60 * 60 seconds.
@@ -450,7 +1089,7 @@ pub fn new_std_payload_bundle(src: EndpointID, dst: EndpointID, data: ByteBuffer
         .source(src.clone())
         .report_to(src)
         .creation_timestamp(CreationTimestamp::now())
-        .lifetime(core::time::Duration::from_secs(60 * 60))
+        .lifetime(Duration::from_secs(60 * 60))
         .build()
         .unwrap();
     let mut b = crate::bundle::Bundle::new(
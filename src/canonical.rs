@@ -1,12 +1,16 @@
 use crate::error::Error;
 use crate::error::ErrorList;
+use crate::helpers::{cbor_head_len, cbor_string_len};
 
 use super::bundle::*;
-use super::crc::{CRC_16, CRC_32, CRC_NO, CrcBlock, CrcRawType, CrcValue};
+use super::crc::{
+    CRC_16, CRC_32, CRC_NO, CrcBlock, CrcRawType, CrcStatus, CrcValue, ReceivedEncoding, crc_status,
+};
 use super::eid::*;
 use super::flags::*;
 use core::convert::TryInto;
 use core::fmt;
+use core::time::Duration;
 use serde::de::{SeqAccess, Visitor};
 use serde::ser::{SerializeSeq, Serializer};
 use serde::{Deserialize, Deserializer, Serialize, de};
@@ -53,6 +57,24 @@ pub const BUNDLE_AGE_BLOCK: CanonicalBlockType = 7;
 // section 4.3.3.
 pub const HOP_COUNT_BLOCK: CanonicalBlockType = 10;
 
+// INTEGRITY_BLOCK and CONFIDENTIALITY_BLOCK are the BlockTypes of the Bundle
+// Security Protocol, RFC 9172 section 3.3.
+pub const INTEGRITY_BLOCK: CanonicalBlockType = 11;
+pub const CONFIDENTIALITY_BLOCK: CanonicalBlockType = 12;
+
+/// Returns a human readable name for well-known block types.
+pub fn block_type_name(block_type: CanonicalBlockType) -> Option<&'static str> {
+    match block_type {
+        PAYLOAD_BLOCK => Some("payload"),
+        PREVIOUS_NODE_BLOCK => Some("previous_node"),
+        BUNDLE_AGE_BLOCK => Some("bundle_age"),
+        HOP_COUNT_BLOCK => Some("hop_count"),
+        INTEGRITY_BLOCK => Some("block_integrity"),
+        CONFIDENTIALITY_BLOCK => Some("block_confidentiality"),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct CanonicalBlockBuilder {
     block_type: CanonicalBlockType,
@@ -62,10 +84,16 @@ pub struct CanonicalBlockBuilder {
     data: Option<CanonicalData>,
 }
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum CanonicalBuilderError {
     #[error("Missing canoncial data")]
     MissingData,
+    #[error("Block data does not match block type {0}")]
+    DataMismatch(CanonicalBlockType),
+    #[error("Payload block must have block number 1, got {0}")]
+    PayloadBlockNumber(u64),
+    #[error("Hop limit must be between 1 and 255 and not below the hop count")]
+    InvalidHopLimit,
 }
 
 impl CanonicalBlockBuilder {
@@ -94,12 +122,31 @@ impl CanonicalBlockBuilder {
     }
     pub fn build(self) -> Result<CanonicalBlock, CanonicalBuilderError> {
         if let Some(data) = self.data {
+            let matches = match self.block_type {
+                PAYLOAD_BLOCK => matches!(data, CanonicalData::Data(_)),
+                HOP_COUNT_BLOCK => matches!(data, CanonicalData::HopCount(_, _)),
+                BUNDLE_AGE_BLOCK => matches!(data, CanonicalData::BundleAge(_)),
+                PREVIOUS_NODE_BLOCK => matches!(data, CanonicalData::PreviousNode(_)),
+                _ => matches!(data, CanonicalData::Unknown(_)),
+            };
+            if !matches {
+                return Err(CanonicalBuilderError::DataMismatch(self.block_type));
+            }
+            if self.block_type == PAYLOAD_BLOCK && self.block_number != PAYLOAD_BLOCK_NUMBER {
+                return Err(CanonicalBuilderError::PayloadBlockNumber(self.block_number));
+            }
+            if let CanonicalData::HopCount(limit, count) = data
+                && (limit == 0 || count > limit)
+            {
+                return Err(CanonicalBuilderError::InvalidHopLimit);
+            }
             Ok(CanonicalBlock {
                 block_type: self.block_type,
                 block_number: self.block_number,
                 block_control_flags: self.block_control_flags,
                 crc: self.crc,
                 data,
+                received: ReceivedEncoding::default(),
             })
         } else {
             Err(CanonicalBuilderError::MissingData)
@@ -108,13 +155,34 @@ impl CanonicalBlockBuilder {
 }
 
 //#[derive(Debug, Serialize_tuple, Deserialize_tuple, Clone)]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct CanonicalBlock {
     pub block_type: CanonicalBlockType,
     pub block_number: u64,
     pub block_control_flags: BlockControlFlagsType,
     pub crc: CrcValue,
     data: CanonicalData,
+    pub(crate) received: ReceivedEncoding<CanonicalFields>,
+}
+
+/// Fields of a canonical block that can be changed without invalidating its received encoding.
+pub(crate) type CanonicalFields = (
+    CanonicalBlockType,
+    CanonicalBlockNumberType,
+    BlockControlFlagsType,
+    CrcValue,
+);
+
+impl fmt::Debug for CanonicalBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CanonicalBlock")
+            .field("block_type", &self.block_type)
+            .field("block_number", &self.block_number)
+            .field("block_control_flags", &self.block_control_flags)
+            .field("crc", &self.crc)
+            .field("data", &self.data)
+            .finish()
+    }
 }
 
 impl Serialize for CanonicalBlock {
@@ -251,6 +319,7 @@ impl<'de> Deserialize<'de> for CanonicalBlock {
                     block_control_flags,
                     crc,
                     data,
+                    received: ReceivedEncoding::default(),
                 })
             }
         }
@@ -268,8 +337,12 @@ impl CrcBlock for CanonicalBlock {
     fn crc_value(&self) -> &CrcValue {
         &self.crc
     }
+    fn received_crc(&self) -> Option<&CrcValue> {
+        self.received.current_crc(&self.fields())
+    }
     fn set_crc(&mut self, crc: CrcValue) {
         self.crc = crc;
+        self.received.invalidate();
     }
 }
 impl Block for CanonicalBlock {
@@ -290,6 +363,7 @@ pub fn new_canonical_block(
         block_control_flags,
         crc: CrcValue::CrcNo,
         data,
+        received: ReceivedEncoding::default(),
     }
 }
 
@@ -301,9 +375,28 @@ impl CanonicalBlock {
             block_control_flags: 0,
             crc: CrcValue::CrcNo,
             data: CanonicalData::Data(Vec::new()),
+            received: ReceivedEncoding::default(),
         }
     }
 
+    /// Length of the CBOR encoding of the block, computed without encoding it.
+    ///
+    /// CRC values take up their full size even if not calculated yet.
+    pub fn encoded_len(&self) -> usize {
+        let data_len = match &self.data {
+            CanonicalData::Data(buf) | CanonicalData::Unknown(buf) => buf.len(),
+            // block-type-specific data is wrapped in a byte string
+            data => data.encoded_len(),
+        };
+        1 // array head, at most 6 elements
+            + cbor_head_len(self.block_type)
+            + cbor_head_len(self.block_number)
+            + cbor_head_len(self.block_control_flags.into())
+            + cbor_head_len(self.crc.to_code().into())
+            + cbor_string_len(data_len)
+            + self.crc.bytes().map_or(0, |crc| cbor_string_len(crc.len()))
+    }
+
     pub fn validate(&self) -> Result<(), ErrorList> {
         let mut errors: ErrorList = Vec::new();
 
@@ -314,8 +407,16 @@ impl CanonicalBlock {
         if let Err(err) = self.extension_validation() {
             errors.push(err);
         }
-        if self.has_crc() && !self.clone().check_crc() {
-            errors.push(Error::CanonicalBlockError("CRC check failed".to_string()));
+        match crc_status(self) {
+            CrcStatus::Absent | CrcStatus::Valid => {}
+            CrcStatus::Invalid => errors.push(Error::CanonicalBlockError(format!(
+                "CRC check failed for block {}",
+                self.block_number
+            ))),
+            CrcStatus::Unsupported(code) => errors.push(Error::CanonicalBlockError(format!(
+                "unsupported CRC type {} for block {}",
+                code, self.block_number
+            ))),
         }
 
         if errors.is_empty() {
@@ -381,8 +482,21 @@ impl CanonicalBlock {
     pub fn data(&self) -> &CanonicalData {
         &self.data
     }
+    pub(crate) fn fields(&self) -> CanonicalFields {
+        (
+            self.block_type,
+            self.block_number,
+            self.block_control_flags,
+            self.crc.clone(),
+        )
+    }
+    /// The received bytes of the block, as long as it has not been changed since decoding.
+    pub(crate) fn received_raw(&self) -> Option<&[u8]> {
+        self.received.unchanged(&self.fields())
+    }
     pub fn set_data(&mut self, data: CanonicalData) {
         self.data = data;
+        self.received.invalidate();
     }
     pub fn payload_data(&self) -> Option<&ByteBuffer> {
         match &self.data {
@@ -415,6 +529,7 @@ impl CanonicalBlock {
         }
         false
     }
+    /// Set the bundle age in milliseconds, see [`CanonicalBlock::set_bundle_age`].
     pub fn bundle_age_update(&mut self, age: u128) -> bool {
         if self.bundle_age_get().is_some() {
             self.set_data(CanonicalData::BundleAge(age.try_into().unwrap()));
@@ -422,6 +537,7 @@ impl CanonicalBlock {
         }
         false
     }
+    /// Get the bundle age in milliseconds, see [`CanonicalBlock::bundle_age`].
     pub fn bundle_age_get(&self) -> Option<u128> {
         if self.block_type == BUNDLE_AGE_BLOCK
             && let CanonicalData::BundleAge(age) = self.data()
@@ -430,6 +546,24 @@ impl CanonicalBlock {
         }
         None
     }
+    /// Get the bundle age of a bundle age block.
+    pub fn bundle_age(&self) -> Option<Duration> {
+        if self.block_type == BUNDLE_AGE_BLOCK
+            && let CanonicalData::BundleAge(age) = self.data()
+        {
+            return Some(Duration::from_millis(*age));
+        }
+        None
+    }
+    /// Set the bundle age of a bundle age block, encoded with millisecond precision (RFC 9171: Sec. 4.4.2).
+    pub fn set_bundle_age(&mut self, age: Duration) -> bool {
+        if self.bundle_age_get().is_some() {
+            let millis = u64::try_from(age.as_millis()).unwrap_or(u64::MAX);
+            self.set_data(CanonicalData::BundleAge(millis));
+            return true;
+        }
+        false
+    }
     pub fn previous_node_update(&mut self, nodeid: EndpointID) -> bool {
         if self.previous_node_get().is_some() {
             self.set_data(CanonicalData::PreviousNode(nodeid));
@@ -461,6 +595,18 @@ impl CanonicalData {
     pub fn to_cbor(&self) -> ByteBuffer {
         serde_cbor::to_vec(&self).expect("CanonicalData encoding error")
     }
+    /// Length of the CBOR encoding returned by [`CanonicalData::to_cbor`].
+    pub(crate) fn encoded_len(&self) -> usize {
+        match self {
+            CanonicalData::HopCount(limit, count) => {
+                1 + cbor_head_len((*limit).into()) + cbor_head_len((*count).into())
+            }
+            CanonicalData::Data(buf) | CanonicalData::Unknown(buf) => cbor_string_len(buf.len()),
+            CanonicalData::BundleAge(age) => cbor_head_len(*age),
+            CanonicalData::PreviousNode(eid) => eid.encoded_len(),
+            CanonicalData::DecodingError => 1, // null
+        }
+    }
 }
 
 /// the hop count limit must be between 1 and 255 (RFC 9171: Sec. 4.4.3)
@@ -519,3 +665,116 @@ pub fn new_bundle_age_block(
         .build()
         .unwrap()
 }
+
+/// Shared setters and the conversion into a [`CanonicalBlock`] for the typed extension blocks.
+///
+/// The resulting block has block number 0, the actual number is assigned by
+/// [`Bundle::insert_block`].
+macro_rules! extension_block {
+    ($name:ident, $block_type:expr, |$blck:ident| $data:expr) => {
+        impl $name {
+            /// Set the block processing control flags.
+            pub fn flags(mut self, flags: BlockControlFlags) -> Self {
+                self.flags = flags.bits();
+                self
+            }
+            /// Set the CRC type used for this block.
+            pub fn crc(mut self, crc_type: CrcRawType) -> Self {
+                self.crc_type = crc_type;
+                self
+            }
+        }
+
+        impl From<$name> for CanonicalBlock {
+            fn from($blck: $name) -> CanonicalBlock {
+                let mut cblock = CanonicalBlock {
+                    block_type: $block_type,
+                    block_number: 0,
+                    block_control_flags: $blck.flags,
+                    crc: CrcValue::CrcNo,
+                    data: $data,
+                    received: ReceivedEncoding::default(),
+                };
+                cblock.set_crc_type($blck.crc_type);
+                cblock
+            }
+        }
+    };
+}
+
+/// Hop count extension block (RFC 9171: Sec. 4.4.3).
+#[derive(Debug, Clone, PartialEq)]
+pub struct HopCountBlock {
+    limit: u8,
+    count: u8,
+    flags: BlockControlFlagsType,
+    crc_type: CrcRawType,
+}
+
+impl HopCountBlock {
+    /// Create a hop count block, the limit must be between 1 and 255.
+    pub fn new(limit: u8) -> Result<Self, CanonicalBuilderError> {
+        if limit == 0 {
+            return Err(CanonicalBuilderError::InvalidHopLimit);
+        }
+        Ok(HopCountBlock {
+            limit,
+            count: 0,
+            flags: 0,
+            crc_type: CRC_NO,
+        })
+    }
+    /// Set the number of hops already taken.
+    pub fn count(mut self, count: u8) -> Self {
+        self.count = count;
+        self
+    }
+}
+
+extension_block!(HopCountBlock, HOP_COUNT_BLOCK, |blck| {
+    CanonicalData::HopCount(blck.limit, blck.count)
+});
+
+/// Bundle age extension block (RFC 9171: Sec. 4.4.2).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleAgeBlock {
+    age: Duration,
+    flags: BlockControlFlagsType,
+    crc_type: CrcRawType,
+}
+
+impl BundleAgeBlock {
+    pub fn new(age: Duration) -> Self {
+        BundleAgeBlock {
+            age,
+            flags: 0,
+            crc_type: CRC_NO,
+        }
+    }
+}
+
+extension_block!(BundleAgeBlock, BUNDLE_AGE_BLOCK, |blck| {
+    CanonicalData::BundleAge(blck.age.as_millis() as u64)
+});
+
+/// Previous node extension block (RFC 9171: Sec. 4.4.1).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviousNodeBlock {
+    node: EndpointID,
+    flags: BlockControlFlagsType,
+    crc_type: CrcRawType,
+}
+
+impl PreviousNodeBlock {
+    pub fn new(node: EndpointID) -> Self {
+        PreviousNodeBlock {
+            node,
+            flags: 0,
+            crc_type: CRC_NO,
+        }
+    }
+}
+
+extension_block!(PreviousNodeBlock, PREVIOUS_NODE_BLOCK, |blck| {
+    CanonicalData::PreviousNode(blck.node)
+});
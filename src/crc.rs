@@ -1,4 +1,9 @@
 use super::bundle::*;
+use super::canonical::CanonicalBlockType;
+use core::borrow::Borrow;
+use core::ops::Range;
+use serde::Serialize;
+use thiserror::Error;
 
 /******************************
  *
@@ -50,6 +55,87 @@ impl CrcValue {
         }
     }
 }
+/// Byte span and checksum of the received encoding of a block.
+///
+/// `S` holds the fields of the block as decoded. The received checksum and bytes are only
+/// trusted while the block still has these fields, changes through accessors drop them right away.
+///
+/// Not part of the value of a block and thus ignored when comparing blocks.
+#[derive(Debug, Clone)]
+pub(crate) struct ReceivedEncoding<S> {
+    /// Byte range of the block within its encoded bundle.
+    pub(crate) span: Option<Range<usize>>,
+    /// Checksum over the received bytes, cleared once the block is changed.
+    crc: Option<CrcValue>,
+    /// Received bytes of the block, only kept when preserving the encoding.
+    pub(crate) raw: Option<ByteBuffer>,
+    /// Header of the enclosing bundle array, kept with the primary block.
+    pub(crate) array_header: Option<ByteBuffer>,
+    /// Fields of the block as decoded, `None` once the block has been changed.
+    decoded: Option<S>,
+}
+
+impl<S> Default for ReceivedEncoding<S> {
+    fn default() -> Self {
+        ReceivedEncoding {
+            span: None,
+            crc: None,
+            raw: None,
+            array_header: None,
+            decoded: None,
+        }
+    }
+}
+
+impl<S> ReceivedEncoding<S> {
+    /// Record the encoding `data` of a block decoded with `fields`, located at `span` within its bundle.
+    pub(crate) fn new<T: CrcBlock>(blck: &T, fields: S, data: &[u8], span: Range<usize>) -> Self {
+        let crc = match blck.crc_type() {
+            crc_type @ (CRC_16 | CRC_32) => Some(crc_of_encoded(crc_type, data)),
+            _ => None,
+        };
+        ReceivedEncoding {
+            span: Some(span),
+            crc,
+            decoded: Some(fields),
+            ..Default::default()
+        }
+    }
+    /// Forget the received checksum and bytes after the block has been changed.
+    pub(crate) fn invalidate(&mut self) {
+        self.crc = None;
+        self.raw = None;
+        self.decoded = None;
+    }
+    /// Whether a block with `fields` is unchanged since it was decoded.
+    fn is_current<T: PartialEq + ?Sized>(&self, fields: &T) -> bool
+    where
+        S: Borrow<T>,
+    {
+        self.decoded.as_ref().is_some_and(|d| d.borrow() == fields)
+    }
+    /// The received checksum, as long as the block still has `fields`.
+    pub(crate) fn current_crc<T: PartialEq + ?Sized>(&self, fields: &T) -> Option<&CrcValue>
+    where
+        S: Borrow<T>,
+    {
+        self.crc.as_ref().filter(|_| self.is_current(fields))
+    }
+    /// The received bytes, as long as the block still has `fields`.
+    pub(crate) fn unchanged<T: PartialEq + ?Sized>(&self, fields: &T) -> Option<&[u8]>
+    where
+        S: Borrow<T>,
+    {
+        self.raw.as_deref().filter(|_| self.is_current(fields))
+    }
+}
+
+impl<S> PartialEq for ReceivedEncoding<S> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
 pub(crate) const CRC16_EMPTY: [u8; 2] = [0; 2];
 pub(crate) const CRC32_EMPTY: [u8; 4] = [0; 4];
 
@@ -57,6 +143,20 @@ pub const CRC_NO: CrcRawType = 0;
 pub const CRC_16: CrcRawType = 1;
 pub const CRC_32: CrcRawType = 2;
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CrcError {
+    #[error("unknown crc type `{0}`")]
+    UnknownCrcType(CrcRawType),
+}
+
+/// Checks that `crc_type` is one of [`CRC_NO`], [`CRC_16`] or [`CRC_32`].
+pub fn validate_crc_type(crc_type: CrcRawType) -> Result<CrcRawType, CrcError> {
+    if crc_type > CRC_32 {
+        return Err(CrcError::UnknownCrcType(crc_type));
+    }
+    Ok(crc_type)
+}
+
 pub trait CRCFuncations {
     fn to_string(self) -> String;
 }
@@ -116,41 +216,144 @@ pub trait CrcBlock: Block + Clone {
     fn crc_type(&self) -> CrcRawType {
         self.crc_value().to_code()
     }
+    /// Checksum computed over the received encoding, if the block was decoded from CBOR
+    /// and has not been changed since.
+    fn received_crc(&self) -> Option<&CrcValue> {
+        None
+    }
     fn crc_value(&self) -> &CrcValue;
     fn set_crc(&mut self, crc: CrcValue);
 }
 
-pub fn calculate_crc<T: CrcBlock + Block>(blck: &mut T) -> CrcValue {
+/// Checksum of a CBOR encoded block, taking its trailing CRC value as zero.
+///
+/// The CRC is always the last element of a block, so no re-encoding is needed
+/// to blank it out. Indefinite-length block arrays are handled as well.
+pub fn crc_of_encoded(crc_type: CrcRawType, data: &[u8]) -> CrcValue {
+    let crc_len = match crc_type {
+        CRC_NO => return CrcValue::CrcNo,
+        CRC_16 => CRC16_EMPTY.len(),
+        CRC_32 => CRC32_EMPTY.len(),
+        _ => panic!("Unknown crc type"),
+    };
+    let (head, tail) = crc_split(data, crc_len);
+    if crc_type == CRC_16 {
+        // also tried crc16 crate, not a bit faster
+        let mut digest = X25.digest();
+        digest.update(head);
+        digest.update(&CRC16_EMPTY);
+        digest.update(tail);
+        CrcValue::Crc16(digest.finalize().to_be_bytes())
+    } else {
+        // also tried crc32fast, was not significantly faster
+        let mut digest = CASTAGNOLI.digest();
+        digest.update(head);
+        digest.update(&CRC32_EMPTY);
+        digest.update(tail);
+        CrcValue::Crc32(digest.finalize().to_be_bytes())
+    }
+}
+
+/// Copy of the CBOR encoded block `data` with its trailing CRC value zeroed.
+///
+/// Blocks without CRC or with an unknown CRC type are copied unchanged.
+pub fn blank_crc(crc_type: CrcRawType, data: &[u8]) -> ByteBuffer {
+    let crc_len = match crc_type {
+        CRC_16 => CRC16_EMPTY.len(),
+        CRC_32 => CRC32_EMPTY.len(),
+        _ => return data.to_vec(),
+    };
+    let (head, tail) = crc_split(data, crc_len);
+    let mut blanked = Vec::with_capacity(data.len());
+    blanked.extend_from_slice(head);
+    blanked.resize(data.len() - tail.len(), 0);
+    blanked.extend_from_slice(tail);
+    blanked
+}
+
+/// Split an encoded block around its CRC value of `crc_len` bytes.
+///
+/// Returns the bytes before and after the CRC value, the latter being the
+/// break mark of an indefinite-length array.
+fn crc_split(data: &[u8], crc_len: usize) -> (&[u8], &[u8]) {
+    let end = if data.first() == Some(&0x9f) && data.last() == Some(&0xff) {
+        data.len() - 1
+    } else {
+        data.len()
+    };
+    let start = end.saturating_sub(crc_len);
+    (&data[..start], &data[end..])
+}
+
+/// Append the CBOR encoding of a block to `buf`, filling in its CRC on the fly.
+///
+/// The block is encoded once and the checksum patched into the written bytes,
+/// the new CRC value is also stored in the block.
+pub(crate) fn write_with_crc<T: CrcBlock + Serialize>(blck: &mut T, buf: &mut Vec<u8>) {
+    let start = buf.len();
+    serde_cbor::to_writer(&mut *buf, blck).expect("Error serializing block as cbor.");
+    let crc = crc_of_encoded(blck.crc_type(), &buf[start..]);
+    if let Some(bytes) = crc.bytes() {
+        let end = buf.len();
+        buf[end - bytes.len()..].copy_from_slice(bytes);
+    }
+    blck.set_crc(crc);
+}
+
+pub fn calculate_crc<T: CrcBlock + Block>(blck: &T) -> CrcValue {
     match blck.crc_type() {
         CRC_NO => CrcValue::CrcNo,
-        CRC_16 => {
-            let crc_bak = blck.crc_value().clone(); // Backup original crc
-            blck.reset_crc(); // set empty crc
-            let data = blck.to_cbor(); // TODO: optimize this encoding away
-            // also tried crc16 crate, not a bit faster
-            let chksm = X25.checksum(&data);
-            let output_crc = chksm.to_be_bytes();
-            blck.set_crc(crc_bak); // restore orginal crc
-            CrcValue::Crc16(output_crc)
-        }
-        CRC_32 => {
-            let crc_bak = blck.crc_value().clone(); // Backup original crc
-            blck.reset_crc(); // set empty crc
-            let data = blck.to_cbor(); // TODO: optimize this encoding away
-            // also tried crc32fast, was not significantly faster
-            let chksm = CASTAGNOLI.checksum(&data);
-            let output_crc = chksm.to_be_bytes();
-            blck.set_crc(crc_bak); // restore orginal crc
-            CrcValue::Crc32(output_crc)
-        }
-        _ => {
-            panic!("Unknown crc type");
-        }
+        crc_type => crc_of_encoded(crc_type, &blck.to_cbor()),
     }
 }
-pub fn check_crc<T: CrcBlock + Block>(blck: &mut T) -> bool {
-    if !blck.has_crc() {
-        return !blck.has_crc();
+pub fn check_crc<T: CrcBlock + Block>(blck: &T) -> bool {
+    matches!(crc_status(blck), CrcStatus::Absent | CrcStatus::Valid)
+}
+
+/// Outcome of checking the CRC of a single block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrcStatus {
+    /// The block carries no CRC.
+    Absent,
+    Valid,
+    Invalid,
+    /// The CRC type is unknown and can not be checked.
+    Unsupported(CrcRawType),
+}
+
+/// Check the CRC of a block.
+///
+/// Blocks decoded from CBOR are checked against their received bytes, all
+/// others against their current encoding.
+pub fn crc_status<T: CrcBlock + Block>(blck: &T) -> CrcStatus {
+    let expected = match (blck.crc_value(), blck.received_crc()) {
+        (CrcValue::CrcNo, _) => return CrcStatus::Absent,
+        (CrcValue::Unknown(code), _) => return CrcStatus::Unsupported(*code),
+        (_, Some(received)) => received.clone(),
+        (_, None) => calculate_crc(blck),
+    };
+    if expected.bytes() == blck.crc() {
+        CrcStatus::Valid
+    } else {
+        CrcStatus::Invalid
+    }
+}
+
+/// CRC check result of one block of a bundle, see [`crate::Bundle::crc_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockCrcReport {
+    /// Block number, zero for the primary block.
+    pub block_number: u64,
+    /// Block type, `None` for the primary block.
+    pub block_type: Option<CanonicalBlockType>,
+    pub crc_type: CrcRawType,
+    /// Byte range of the block within the received bundle, if decoded from CBOR.
+    pub span: Option<Range<usize>>,
+    pub status: CrcStatus,
+}
+
+impl BlockCrcReport {
+    pub fn is_valid(&self) -> bool {
+        matches!(self.status, CrcStatus::Absent | CrcStatus::Valid)
     }
-    calculate_crc(blck).bytes() == blck.crc()
 }
@@ -1,8 +1,9 @@
+use crate::helpers::cbor_head_len;
 use core::fmt;
-use core::sync::atomic::{AtomicUsize, Ordering};
 use core::time::Duration;
 use humantime::format_rfc3339;
 use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, PoisonError};
 use std::time::UNIX_EPOCH;
 
 /// Time since the year 2k in milliseconds
@@ -59,6 +60,10 @@ impl CreationTimestamp {
     pub fn dtntime(&self) -> DtnTime {
         self.0
     }
+    /// Length of the CBOR encoding of the timestamp.
+    pub(crate) fn encoded_len(&self) -> usize {
+        1 + cbor_head_len(self.0) + cbor_head_len(self.1)
+    }
     /// Create a new timestamp with automatic sequence counting
     ///
     /// # Example
@@ -77,14 +82,35 @@ impl CreationTimestamp {
     /// assert_eq!(time3.seqno(), 0);
     /// ```
     pub fn now() -> CreationTimestamp {
-        static LAST_CREATION_TIMESTAMP: AtomicUsize = AtomicUsize::new(0);
-        static LAST_CREATION_SEQ: AtomicUsize = AtomicUsize::new(0);
+        static GLOBAL_SEQUENCE: SequenceCounter = SequenceCounter::new();
+        GLOBAL_SEQUENCE.next()
+    }
+}
+
+/// Source of creation timestamps with a sequence number that restarts for every new millisecond.
+///
+/// [`CreationTimestamp::now`] shares one process-wide counter, nodes running in the same
+/// process should each own a counter instead.
+#[derive(Debug, Default)]
+pub struct SequenceCounter {
+    last: Mutex<(DtnTime, u64)>,
+}
+
+impl SequenceCounter {
+    pub const fn new() -> SequenceCounter {
+        SequenceCounter {
+            last: Mutex::new((0, 0)),
+        }
+    }
+    /// Next creation timestamp based on the current time.
+    pub fn next(&self) -> CreationTimestamp {
         let now = dtn_time_now();
-        if now != LAST_CREATION_TIMESTAMP.swap(now as usize, Ordering::Relaxed) as u64 {
-            LAST_CREATION_SEQ.store(0, Ordering::SeqCst)
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        if last.0 != now {
+            *last = (now, 0);
         }
-        let seq = LAST_CREATION_SEQ.fetch_add(1, Ordering::SeqCst);
-
-        CreationTimestamp::with_time_and_seq(now, seq as u64)
+        let seq = last.1;
+        last.1 += 1;
+        CreationTimestamp::with_time_and_seq(now, seq)
     }
 }
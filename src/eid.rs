@@ -1,3 +1,4 @@
+use crate::helpers::{cbor_head_len, cbor_string_len};
 use core::convert::TryFrom;
 use core::convert::TryInto;
 use core::fmt;
@@ -352,6 +353,18 @@ impl fmt::Display for EndpointID {
 }
 
 impl EndpointID {
+    /// Length of the CBOR encoding of the endpoint.
+    pub(crate) fn encoded_len(&self) -> usize {
+        let (scheme, ssp) = match self {
+            EndpointID::Dtn(scheme, addr) => (*scheme, cbor_string_len(addr.0.len())),
+            EndpointID::DtnNone(scheme, none) => (*scheme, cbor_head_len(u64::from(*none))),
+            EndpointID::Ipn(scheme, addr) => (
+                *scheme,
+                1 + cbor_head_len(addr.node_number()) + cbor_head_len(addr.service_number()),
+            ),
+        };
+        1 + cbor_head_len(u64::from(scheme)) + ssp
+    }
     /// Returns the plain node name without URL scheme
     pub fn node(&self) -> Option<String> {
         match self {
@@ -2,7 +2,9 @@ use core::fmt;
 
 use thiserror::Error;
 
+use crate::administrative_record::{AdministrativeRecordError, BibeError};
 use crate::eid::EndpointIdError;
+use crate::fragmentation::FragmentationError;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -12,6 +14,9 @@ pub enum Error {
     DtnTimeError(String),
     CrcError(String),
     BundleError(String),
+    AdministrativeRecordError(#[from] AdministrativeRecordError),
+    FragmentationError(#[from] FragmentationError),
+    BibeError(#[from] BibeError),
     BundleControlFlagsError(String),
     BlockControlFlagsError(String),
     JsonDecodeError(#[from] serde_json::Error),
@@ -1,18 +1,134 @@
 use std::{
+    cell::RefCell,
     convert::TryInto,
     ffi::{CStr, CString},
     os::raw::c_char,
 };
 
 use crate::{
-    Bundle, CreationTimestamp, EndpointID, flags::BlockControlFlags, flags::BundleControlFlags,
-    helpers, new_payload_block, primary,
+    BUNDLE_AGE_BLOCK, Bundle, CreationTimestamp, EndpointID, HOP_COUNT_BLOCK,
+    administrative_record::{self, AdministrativeRecord, StatusReportError},
+    crc::CrcRawType,
+    flags::BlockControlFlags,
+    flags::BundleControlFlags,
+    helpers, new_bundle_age_block, new_hop_count_block, new_payload_block, new_previous_node_block,
+    primary,
+    primary::PrimaryBlockBuilder,
 };
 
+/// Stable error codes returned by the FFI functions.
+///
+/// A description of the last error on the calling thread is available via `bp7_last_error_message`.
+///
+/// cbindgen:prefix-with-name
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bp7ErrorCode {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    InvalidEndpoint = 3,
+    DecodeFailed = 4,
+    InvalidBundle = 5,
+    MissingBlock = 6,
+    DuplicateBlock = 7,
+    InvalidArgument = 8,
+    LifetimeExceeded = 9,
+    NotAdministrativeRecord = 10,
+    StatusReportNotPermitted = 11,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<(Bp7ErrorCode, String)> =
+        const { RefCell::new((Bp7ErrorCode::Ok, String::new())) };
+}
+
+/// Records the last error of the calling thread and returns its code.
+fn set_error(code: Bp7ErrorCode, msg: impl Into<String>) -> Bp7ErrorCode {
+    LAST_ERROR.with(|e| *e.borrow_mut() = (code, msg.into()));
+    code
+}
+
+fn clear_error() {
+    set_error(Bp7ErrorCode::Ok, "");
+}
+
+/// Runs `f`, resetting the last error beforehand, and converts the result into an error code.
+fn ffi_code(f: impl FnOnce() -> Result<(), Bp7ErrorCode>) -> Bp7ErrorCode {
+    clear_error();
+    match f() {
+        Ok(()) => Bp7ErrorCode::Ok,
+        Err(code) => code,
+    }
+}
+
+/// Runs `f`, resetting the last error beforehand, and returns the boxed result or a null pointer.
+fn ffi_ptr<T>(f: impl FnOnce() -> Result<T, Bp7ErrorCode>) -> *mut T {
+    clear_error();
+    match f() {
+        Ok(v) => Box::into_raw(Box::new(v)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+unsafe fn deref_mut<'a, T>(ptr: *mut T, name: &str) -> Result<&'a mut T, Bp7ErrorCode> {
+    unsafe {
+        ptr.as_mut()
+            .ok_or_else(|| set_error(Bp7ErrorCode::NullPointer, format!("{} is null", name)))
+    }
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, Bp7ErrorCode> {
+    if ptr.is_null() {
+        return Err(set_error(
+            Bp7ErrorCode::NullPointer,
+            format!("{} is null", name),
+        ));
+    }
+    unsafe { CStr::from_ptr(ptr) }.to_str().map_err(|e| {
+        set_error(
+            Bp7ErrorCode::InvalidUtf8,
+            format!("{} is not valid UTF-8: {}", name, e),
+        )
+    })
+}
+
+unsafe fn eid_arg(ptr: *const c_char, name: &str) -> Result<EndpointID, Bp7ErrorCode> {
+    let s = unsafe { str_arg(ptr, name)? };
+    s.try_into().map_err(|e: crate::eid::EndpointIdError| {
+        set_error(
+            Bp7ErrorCode::InvalidEndpoint,
+            format!("invalid {} `{}`: {}", name, s, e),
+        )
+    })
+}
+
+unsafe fn buffer_arg<'a>(ptr: *const Buffer, name: &str) -> Result<&'a [u8], Bp7ErrorCode> {
+    let buf = unsafe { ptr.as_ref() }
+        .ok_or_else(|| set_error(Bp7ErrorCode::NullPointer, format!("{} is null", name)))?;
+    if buf.data.is_null() {
+        return Ok(&[]);
+    }
+    Ok(unsafe { core::slice::from_raw_parts(buf.data, buf.len as usize) })
+}
+
+fn into_buffer(data: Vec<u8>) -> *mut Buffer {
+    let mut buf = data.into_boxed_slice();
+    let data = buf.as_mut_ptr();
+    let len = buf.len() as u32;
+    std::mem::forget(buf);
+    Box::into_raw(Box::new(Buffer { data, len }))
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // strings handled here never contain interior NUL bytes
+    CString::new(s).unwrap_or_default().into_raw()
+}
+
 #[repr(C)]
 pub struct Buffer {
-    data: *mut u8,
-    len: u32,
+    pub data: *mut u8,
+    pub len: u32,
 }
 
 #[repr(C)]
@@ -27,6 +143,8 @@ pub struct BundleMetaData {
     seqno: u64,
     /// The lifetime of a bundle in ms
     lifetime: u64,
+    /// One line description of the bundle
+    summary: *mut c_char,
 }
 
 /// A simple test for the bp7 FFI interface.
@@ -40,25 +158,14 @@ pub extern "C" fn bp7_working() -> u8 {
 /// The returned buffer contains the following bytes: [0x42, 0x43, 0x44, 0x45]
 #[unsafe(no_mangle)]
 pub extern "C" fn bp7_buffer_test() -> *mut Buffer {
-    let input = vec![0x42, 0x43, 0x44, 0x45];
-
-    let mut buf = input.into_boxed_slice();
-    let data = buf.as_mut_ptr();
-    let len = buf.len() as u32;
-    std::mem::forget(buf);
-    Box::into_raw(Box::new(Buffer { data, len }))
+    into_buffer(vec![0x42, 0x43, 0x44, 0x45])
 }
 
 /// Generate a random bundle as a raw buffer.
 #[unsafe(no_mangle)]
 pub extern "C" fn helper_rnd_bundle() -> *mut Buffer {
     let mut bndl = helpers::rnd_bundle(CreationTimestamp::now());
-
-    let mut buf = bndl.to_cbor().into_boxed_slice();
-    let data = buf.as_mut_ptr();
-    let len = buf.len() as u32;
-    std::mem::forget(buf);
-    Box::into_raw(Box::new(Buffer { data, len }))
+    into_buffer(bndl.to_cbor())
 }
 
 /// Free the memory of a given buffer.
@@ -79,7 +186,8 @@ pub unsafe extern "C" fn buffer_free(buf: *mut Buffer) {
 
 /// Try to decode a bundle from a given buffer.
 ///
-/// In case of failure, a null pointer is returned instead of a bundle.
+/// In case of failure, a null pointer is returned instead of a bundle and
+/// the reason is available via `bp7_last_error_code` and `bp7_last_error_message`.
 ///
 /// # Safety
 ///
@@ -87,23 +195,21 @@ pub unsafe extern "C" fn buffer_free(buf: *mut Buffer) {
 /// This function can lead to UB as pointer cannot be validated!
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bundle_from_cbor(ptr: *mut Buffer) -> *mut Bundle {
-    unsafe {
-        assert!(!ptr.is_null());
-        let buf = &mut *ptr;
-        //println!("buf len {}", buf.len);
-        assert!(!buf.data.is_null());
-        let buffer = core::slice::from_raw_parts(buf.data, buf.len as usize);
-        //println!("buffer {}", helpers::hexify(buffer));
-        let bndl: Bundle = buffer
-            .to_owned()
-            .try_into()
-            .expect("failed to load bundle from buffer");
-        if bndl.validate().is_ok() {
-            Box::into_raw(Box::new(bndl))
-        } else {
-            std::ptr::null_mut::<Bundle>()
-        }
-    }
+    ffi_ptr(|| {
+        let buffer = unsafe { buffer_arg(ptr, "buffer")? };
+        let bndl: Bundle = buffer.try_into().map_err(|e: crate::error::Error| {
+            set_error(Bp7ErrorCode::DecodeFailed, e.to_string())
+        })?;
+        validate(&bndl)?;
+        Ok(bndl)
+    })
+}
+
+fn validate(bndl: &Bundle) -> Result<(), Bp7ErrorCode> {
+    bndl.validate().map_err(|errs| {
+        let msgs: Vec<String> = errs.iter().map(|e| e.to_string()).collect();
+        set_error(Bp7ErrorCode::InvalidBundle, msgs.join("\n"))
+    })
 }
 
 /// Encode a given bundle a CBOR byte buffer
@@ -117,16 +223,14 @@ pub unsafe extern "C" fn bundle_to_cbor(bndl: *mut Bundle) -> *mut Buffer {
     unsafe {
         assert!(!bndl.is_null());
         let bndl = &mut *bndl;
-        let mut buf = bndl.to_cbor().into_boxed_slice();
-        let data = buf.as_mut_ptr();
-        let len = buf.len() as u32;
-        std::mem::forget(buf);
-        Box::into_raw(Box::new(Buffer { data, len }))
+        into_buffer(bndl.to_cbor())
     }
 }
 
 /// Create a new bundle with standard configuration and a given payload
 ///
+/// Returns a null pointer if an endpoint is invalid, see `bp7_last_error_message`.
+///
 /// # Safety
 ///
 /// Should only be called from FFI interface.
@@ -138,22 +242,10 @@ pub unsafe extern "C" fn bundle_new_default(
     lifetime: u64,
     ptr: *mut Buffer,
 ) -> *mut Bundle {
-    unsafe {
-        assert!(!src.is_null());
-        let c_str_src = CStr::from_ptr(src);
-
-        let r_src = c_str_src.to_str().unwrap();
-        let src_eid: EndpointID = r_src.try_into().unwrap();
-
-        assert!(!dst.is_null());
-        let c_str_dst = CStr::from_ptr(dst);
-        let r_dst = c_str_dst.to_str().unwrap();
-        let dst_eid: EndpointID = r_dst.try_into().unwrap();
-
-        assert!(!ptr.is_null());
-        let payload = &mut *ptr;
-        assert!(!payload.data.is_null());
-        let data = core::slice::from_raw_parts(payload.data, payload.len as usize);
+    ffi_ptr(|| unsafe {
+        let src_eid = eid_arg(src, "source")?;
+        let dst_eid = eid_arg(dst, "destination")?;
+        let data = buffer_arg(ptr, "payload")?;
 
         let pblock = primary::PrimaryBlockBuilder::default()
             .bundle_control_flags(BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED.bits())
@@ -163,7 +255,7 @@ pub unsafe extern "C" fn bundle_new_default(
             .creation_timestamp(CreationTimestamp::now())
             .lifetime(core::time::Duration::from_millis(lifetime))
             .build()
-            .unwrap();
+            .map_err(|e| set_error(Bp7ErrorCode::InvalidArgument, e.to_string()))?;
         let mut b = Bundle::new(
             pblock,
             vec![new_payload_block(
@@ -173,8 +265,8 @@ pub unsafe extern "C" fn bundle_new_default(
         );
         b.set_crc(crate::crc::CRC_NO);
         b.sort_canonicals();
-        Box::into_raw(Box::new(b))
-    }
+        Ok(b)
+    })
 }
 
 /// Frees the memory of a given bundle.
@@ -202,20 +294,14 @@ pub unsafe extern "C" fn bundle_free(ptr: *mut Bundle) {
 pub unsafe extern "C" fn bundle_get_metadata(bndl: *mut Bundle) -> *mut BundleMetaData {
     unsafe {
         assert!(!bndl.is_null());
-        let bndl = &mut *bndl;
-        let timestamp = bndl.primary.creation_timestamp.dtntime();
-        let seqno = bndl.primary.creation_timestamp.seqno();
-        let lifetime = bndl.primary.lifetime.as_millis() as u64;
-        let src_str = CString::new(bndl.primary.source.to_string()).unwrap();
-        let src = src_str.into_raw();
-        let dst_str = CString::new(bndl.primary.destination.to_string()).unwrap();
-        let dst = dst_str.into_raw();
+        let summary = (*bndl).summary();
         Box::into_raw(Box::new(BundleMetaData {
-            src,
-            dst,
-            timestamp,
-            seqno,
-            lifetime,
+            src: into_c_string(summary.source.to_string()),
+            dst: into_c_string(summary.destination.to_string()),
+            timestamp: summary.creation_timestamp.dtntime(),
+            seqno: summary.creation_timestamp.seqno(),
+            lifetime: summary.lifetime.as_millis() as u64,
+            summary: into_c_string(summary.to_string()),
         }))
     }
 }
@@ -230,7 +316,7 @@ pub unsafe extern "C" fn bundle_get_metadata(bndl: *mut Bundle) -> *mut BundleMe
 pub unsafe extern "C" fn bundle_metadata_free(ptr: *mut BundleMetaData) {
     unsafe {
         assert!(!ptr.is_null());
-        let meta = &mut *ptr;
+        let meta = Box::from_raw(ptr);
         if !meta.src.is_null() {
             drop(CString::from_raw(meta.src));
         }
@@ -238,6 +324,10 @@ pub unsafe extern "C" fn bundle_metadata_free(ptr: *mut BundleMetaData) {
         if !meta.dst.is_null() {
             drop(CString::from_raw(meta.dst));
         }
+
+        if !meta.summary.is_null() {
+            drop(CString::from_raw(meta.summary));
+        }
     }
 }
 
@@ -269,11 +359,7 @@ pub unsafe extern "C" fn bundle_payload(bndl: *mut Bundle) -> *mut Buffer {
         if !bndl.is_null() {
             let bndl = &mut *bndl;
             if let Some(payload) = bndl.payload() {
-                let mut buf = payload.clone().into_boxed_slice();
-                let data = buf.as_mut_ptr();
-                let len = buf.len() as u32;
-                std::mem::forget(buf);
-                return Box::into_raw(Box::new(Buffer { data, len }));
+                return into_buffer(payload.clone());
             }
         }
         Box::into_raw(Box::new(Buffer {
@@ -282,3 +368,542 @@ pub unsafe extern "C" fn bundle_payload(bndl: *mut Bundle) -> *mut Buffer {
         }))
     }
 }
+
+/// Get the error code of the last failed FFI call on the calling thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn bp7_last_error_code() -> Bp7ErrorCode {
+    LAST_ERROR.with(|e| e.borrow().0)
+}
+
+/// Get a description of the last failed FFI call on the calling thread.
+///
+/// The returned string must be freed with `bp7_string_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn bp7_last_error_message() -> *mut c_char {
+    LAST_ERROR.with(|e| into_c_string(e.borrow().1.clone()))
+}
+
+/// Frees a string returned by the bp7 FFI.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bp7_string_free(s: *mut c_char) {
+    unsafe {
+        if !s.is_null() {
+            drop(CString::from_raw(s));
+        }
+    }
+}
+
+/// Create a new primary block builder.
+///
+/// The creation timestamp defaults to the time of building and the lifetime to one hour.
+#[unsafe(no_mangle)]
+pub extern "C" fn primary_builder_new() -> *mut PrimaryBlockBuilder {
+    Box::into_raw(Box::new(PrimaryBlockBuilder::default()))
+}
+
+/// Frees a primary block builder.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn primary_builder_free(builder: *mut PrimaryBlockBuilder) {
+    unsafe {
+        if !builder.is_null() {
+            drop(Box::from_raw(builder));
+        }
+    }
+}
+
+unsafe fn update_builder(
+    builder: *mut PrimaryBlockBuilder,
+    f: impl FnOnce(PrimaryBlockBuilder) -> Result<PrimaryBlockBuilder, Bp7ErrorCode>,
+) -> Bp7ErrorCode {
+    ffi_code(|| {
+        let builder = unsafe { deref_mut(builder, "builder")? };
+        // the builder is left untouched if an argument is rejected
+        *builder = f(builder.clone())?;
+        Ok(())
+    })
+}
+
+/// Set the destination endpoint of a primary block builder.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn primary_builder_destination(
+    builder: *mut PrimaryBlockBuilder,
+    eid: *const c_char,
+) -> Bp7ErrorCode {
+    unsafe { update_builder(builder, |b| Ok(b.destination(eid_arg(eid, "destination")?))) }
+}
+
+/// Set the source endpoint of a primary block builder.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn primary_builder_source(
+    builder: *mut PrimaryBlockBuilder,
+    eid: *const c_char,
+) -> Bp7ErrorCode {
+    unsafe { update_builder(builder, |b| Ok(b.source(eid_arg(eid, "source")?))) }
+}
+
+/// Set the report-to endpoint of a primary block builder.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn primary_builder_report_to(
+    builder: *mut PrimaryBlockBuilder,
+    eid: *const c_char,
+) -> Bp7ErrorCode {
+    unsafe { update_builder(builder, |b| Ok(b.report_to(eid_arg(eid, "report_to")?))) }
+}
+
+/// Set the bundle processing control flags of a primary block builder.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn primary_builder_flags(
+    builder: *mut PrimaryBlockBuilder,
+    flags: u64,
+) -> Bp7ErrorCode {
+    unsafe { update_builder(builder, |b| Ok(b.bundle_control_flags(flags))) }
+}
+
+/// Set the creation timestamp (DTN time in ms and sequence number) of a primary block builder.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn primary_builder_creation_timestamp(
+    builder: *mut PrimaryBlockBuilder,
+    time: u64,
+    seqno: u64,
+) -> Bp7ErrorCode {
+    unsafe {
+        update_builder(builder, |b| {
+            Ok(b.creation_timestamp(CreationTimestamp::with_time_and_seq(time, seqno)))
+        })
+    }
+}
+
+/// Set the lifetime in ms of a primary block builder.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn primary_builder_lifetime(
+    builder: *mut PrimaryBlockBuilder,
+    lifetime: u64,
+) -> Bp7ErrorCode {
+    unsafe {
+        update_builder(builder, |b| {
+            Ok(b.lifetime(core::time::Duration::from_millis(lifetime)))
+        })
+    }
+}
+
+fn crc_type_arg(crc_type: CrcRawType) -> Result<CrcRawType, Bp7ErrorCode> {
+    crate::crc::validate_crc_type(crc_type)
+        .map_err(|e| set_error(Bp7ErrorCode::InvalidArgument, e.to_string()))
+}
+
+/// Create a bundle from a primary block builder and a payload.
+///
+/// The builder is not consumed and must still be freed. All blocks use the given CRC type
+/// (0 = none, 1 = CRC16, 2 = CRC32). Returns a null pointer on failure.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_new(
+    builder: *mut PrimaryBlockBuilder,
+    payload: *mut Buffer,
+    crc_type: CrcRawType,
+) -> *mut Bundle {
+    ffi_ptr(|| unsafe {
+        let builder = deref_mut(builder, "builder")?;
+        let data = buffer_arg(payload, "payload")?;
+        let crc_type = crc_type_arg(crc_type)?;
+        let pblock = builder
+            .clone()
+            .build()
+            .map_err(|e| set_error(Bp7ErrorCode::InvalidArgument, e.to_string()))?;
+        let mut b = Bundle::new(
+            pblock,
+            vec![new_payload_block(
+                BlockControlFlags::empty(),
+                data.to_owned(),
+            )],
+        );
+        b.set_crc(crc_type);
+        b.calculate_crc();
+        Ok(b)
+    })
+}
+
+/// Set the CRC type of all blocks (0 = none, 1 = CRC16, 2 = CRC32).
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_set_crc(bndl: *mut Bundle, crc_type: CrcRawType) -> Bp7ErrorCode {
+    ffi_code(|| {
+        let bndl = unsafe { deref_mut(bndl, "bundle")? };
+        bndl.set_crc(crc_type_arg(crc_type)?);
+        bndl.calculate_crc();
+        Ok(())
+    })
+}
+
+/// Get the ID of a bundle, i.e., source, creation timestamp and fragment offset.
+///
+/// The returned string must be freed with `bp7_string_free`.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_id(bndl: *mut Bundle) -> *mut c_char {
+    clear_error();
+    match unsafe { deref_mut(bndl, "bundle") } {
+        Ok(bndl) => into_c_string(bndl.id()),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Validate a bundle.
+///
+/// Returns `InvalidBundle` if the bundle is invalid, all validation errors are
+/// available via `bp7_last_error_message`, one per line.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_validate(bndl: *mut Bundle) -> Bp7ErrorCode {
+    ffi_code(|| validate(unsafe { deref_mut(bndl, "bundle")? }))
+}
+
+fn add_block(bndl: &mut Bundle, block: crate::CanonicalBlock) -> Result<(), Bp7ErrorCode> {
+    bndl.add_block(block)
+        .map(|_| ())
+        .map_err(|e| set_error(Bp7ErrorCode::DuplicateBlock, e.to_string()))
+}
+
+/// Add a hop count block with the given limit (1 to 255).
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_add_hop_count_block(bndl: *mut Bundle, limit: u8) -> Bp7ErrorCode {
+    ffi_code(|| {
+        let bndl = unsafe { deref_mut(bndl, "bundle")? };
+        if limit == 0 {
+            return Err(set_error(
+                Bp7ErrorCode::InvalidArgument,
+                "hop limit must be between 1 and 255",
+            ));
+        }
+        add_block(
+            bndl,
+            new_hop_count_block(0, BlockControlFlags::empty(), limit),
+        )
+    })
+}
+
+/// Add a bundle age block with the given age in ms.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_add_bundle_age_block(bndl: *mut Bundle, age: u64) -> Bp7ErrorCode {
+    ffi_code(|| {
+        let bndl = unsafe { deref_mut(bndl, "bundle")? };
+        add_block(
+            bndl,
+            new_bundle_age_block(0, BlockControlFlags::empty(), age),
+        )
+    })
+}
+
+/// Add a previous node block with the given node ID.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_add_previous_node_block(
+    bndl: *mut Bundle,
+    node: *const c_char,
+) -> Bp7ErrorCode {
+    ffi_code(|| unsafe {
+        let bndl = deref_mut(bndl, "bundle")?;
+        let node = eid_arg(node, "node")?;
+        add_block(
+            bndl,
+            new_previous_node_block(0, BlockControlFlags::empty(), node),
+        )
+    })
+}
+
+fn missing_block(block: &str) -> Bp7ErrorCode {
+    set_error(
+        Bp7ErrorCode::MissingBlock,
+        format!("bundle has no {} block", block),
+    )
+}
+
+/// Read the hop limit and hop count of the hop count block.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_hop_count(
+    bndl: *mut Bundle,
+    limit: *mut u8,
+    count: *mut u8,
+) -> Bp7ErrorCode {
+    ffi_code(|| unsafe {
+        let bndl = deref_mut(bndl, "bundle")?;
+        let (l, c) = bndl
+            .extension_block_by_type(HOP_COUNT_BLOCK)
+            .and_then(|b| b.hop_count_get())
+            .ok_or_else(|| missing_block("hop count"))?;
+        *deref_mut(limit, "limit")? = l;
+        *deref_mut(count, "count")? = c;
+        Ok(())
+    })
+}
+
+/// Read the bundle age in ms from the bundle age block.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_age(bndl: *mut Bundle, age: *mut u64) -> Bp7ErrorCode {
+    ffi_code(|| unsafe {
+        let bndl = deref_mut(bndl, "bundle")?;
+        let a = bndl
+            .extension_block_by_type(BUNDLE_AGE_BLOCK)
+            .and_then(|b| b.bundle_age())
+            .ok_or_else(|| missing_block("bundle age"))?;
+        *deref_mut(age, "age")? = a.as_millis() as u64;
+        Ok(())
+    })
+}
+
+/// Get the node ID of the previous node block.
+///
+/// Returns a null pointer if there is none, the string must be freed with `bp7_string_free`.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_previous_node(bndl: *mut Bundle) -> *mut c_char {
+    clear_error();
+    let node = unsafe { deref_mut(bndl, "bundle") }.and_then(|bndl| {
+        bndl.previous_node()
+            .map(|n| n.to_string())
+            .ok_or_else(|| missing_block("previous node"))
+    });
+    node.map_or(std::ptr::null_mut(), into_c_string)
+}
+
+/// Update hop count, previous node and bundle age before forwarding the bundle.
+///
+/// Returns `LifetimeExceeded` if the hop limit or lifetime is exceeded and the bundle should be deleted.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_update_extensions(
+    bndl: *mut Bundle,
+    local_node: *const c_char,
+    residence_time: u64,
+) -> Bp7ErrorCode {
+    ffi_code(|| unsafe {
+        let bndl = deref_mut(bndl, "bundle")?;
+        let node = eid_arg(local_node, "local_node")?;
+        if bndl.update_extensions(node, core::time::Duration::from_millis(residence_time)) {
+            bndl.calculate_crc();
+            Ok(())
+        } else {
+            Err(set_error(
+                Bp7ErrorCode::LifetimeExceeded,
+                format!("hop limit or lifetime of {} exceeded", bndl.id()),
+            ))
+        }
+    })
+}
+
+/// Contents of a bundle status report.
+///
+/// Status information is indexed by position: 0 = received, 1 = forwarded, 2 = delivered, 3 = deleted.
+#[repr(C)]
+pub struct StatusReportInfo {
+    /// Bit mask of asserted status information, bit n is set if position n is asserted
+    pub asserted: u32,
+    /// Time of each asserted status in DTN time, 0 if not reported
+    pub times: [u64; 4],
+    /// The status report reason code
+    pub reason: u32,
+    /// ID of the bundle this report refers to
+    pub refbundle: *mut c_char,
+    /// Source of the bundle this report refers to
+    pub source: *mut c_char,
+    /// Creation timestamp of the bundle this report refers to in DTN time
+    pub timestamp: u64,
+    /// Sequence number of the bundle this report refers to
+    pub seqno: u64,
+    /// Fragment offset if the report refers to a fragment
+    pub frag_offset: u64,
+    /// Fragment length if the report refers to a fragment, otherwise 0
+    pub frag_len: u64,
+}
+
+/// Create a status report bundle for `orig`, sent from `src`.
+///
+/// `status` is a bit mask of status information positions to assert, see `StatusReportInfo`.
+/// Unless `force` is set, assertions not requested by the original bundle are dropped and
+/// `StatusReportNotPermitted` is reported if none remain. Returns a null pointer on failure.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_new_status_report(
+    orig: *mut Bundle,
+    src: *const c_char,
+    crc_type: CrcRawType,
+    status: u32,
+    reason: u32,
+    force: bool,
+) -> *mut Bundle {
+    ffi_ptr(|| unsafe {
+        let orig = deref_mut(orig, "orig")?;
+        let src = eid_arg(src, "src")?;
+        let crc_type = crc_type_arg(crc_type)?;
+        let items: Vec<u32> = (0..administrative_record::MAX_STATUS_INFORMATION_POS)
+            .filter(|pos| status & (1 << pos) != 0)
+            .collect();
+        administrative_record::status_report_bundle_with(orig, src, crc_type, &items, reason, force)
+            .map_err(|e: StatusReportError| {
+                set_error(Bp7ErrorCode::StatusReportNotPermitted, e.to_string())
+            })
+    })
+}
+
+/// Parse the status report carried by a bundle.
+///
+/// Returns a null pointer if the bundle is no status report, free the result with `status_report_info_free`.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bundle_status_report(bndl: *mut Bundle) -> *mut StatusReportInfo {
+    ffi_ptr(|| {
+        let bndl = unsafe { deref_mut(bndl, "bundle")? };
+        let sr = match bndl.administrative_record() {
+            Ok(AdministrativeRecord::BundleStatusReport(sr)) => sr,
+            Ok(record) => {
+                return Err(set_error(
+                    Bp7ErrorCode::NotAdministrativeRecord,
+                    format!(
+                        "administrative record of type {} is no status report",
+                        record.type_code()
+                    ),
+                ));
+            }
+            Err(e) => {
+                return Err(set_error(
+                    Bp7ErrorCode::NotAdministrativeRecord,
+                    e.to_string(),
+                ));
+            }
+        };
+        let mut info = StatusReportInfo {
+            asserted: 0,
+            times: [0; 4],
+            reason: sr.report_reason,
+            refbundle: into_c_string(sr.refbundle()),
+            source: into_c_string(sr.source_node.to_string()),
+            timestamp: sr.timestamp.dtntime(),
+            seqno: sr.timestamp.seqno(),
+            frag_offset: sr.frag_offset,
+            frag_len: sr.frag_len,
+        };
+        for (pos, item) in sr.status_information.iter().take(4).enumerate() {
+            if item.asserted {
+                info.asserted |= 1 << pos;
+                if item.status_requested {
+                    info.times[pos] = item.time;
+                }
+            }
+        }
+        Ok(info)
+    })
+}
+
+/// Frees a StatusReportInfo struct.
+///
+/// # Safety
+///
+/// Should only be called from FFI interface.
+/// This function can lead to UB as pointer cannot be validated!
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn status_report_info_free(ptr: *mut StatusReportInfo) {
+    unsafe {
+        if ptr.is_null() {
+            return;
+        }
+        let info = Box::from_raw(ptr);
+        bp7_string_free(info.refbundle);
+        bp7_string_free(info.source);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::{Error, ErrorList};
-use bitflags::bitflags;
+use bitflags::{Flags, bitflags};
+use core::fmt;
 
 /******************************
  *
@@ -27,6 +28,13 @@ bitflags! {
     }
 }
 
+/// Flag names joined by ` | `, e.g. `BLOCK_REPLICATE | BLOCK_REMOVE`.
+impl fmt::Display for BlockControlFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        bitflags::parser::to_writer(self, f)
+    }
+}
+
 pub trait BlockValidation {
     fn flags(&self) -> BlockControlFlags;
 
@@ -107,6 +115,13 @@ bitflags! {
     }
 }
 
+/// Flag names joined by ` | `, e.g. `BUNDLE_MUST_NOT_FRAGMENTED | BUNDLE_IS_FRAGMENT`.
+impl fmt::Display for BundleControlFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        bitflags::parser::to_writer(self, f)
+    }
+}
+
 pub trait BundleValidation {
     fn flags(&self) -> BundleControlFlags;
     fn contains(&self, flags: BundleControlFlags) -> bool
@@ -158,3 +173,35 @@ impl BundleValidation for BundleControlFlagsType {
         *self = flags.bits();
     }
 }
+
+/// Names of the flags set in `flags`, remaining bits without a name are given as hex number.
+pub fn flag_names<F: Flags>(flags: F) -> Vec<String>
+where
+    F::Bits: Into<u64>,
+{
+    let mut iter = flags.iter_names();
+    let mut names: Vec<String> = iter.by_ref().map(|(name, _)| name.to_string()).collect();
+    let rest: u64 = iter.remaining().bits().into();
+    if rest != 0 {
+        names.push(format!("{:#x}", rest));
+    }
+    names
+}
+
+/// Inverse of [`flag_names`], fails on unknown names or bits out of range.
+pub fn parse_flag_names<F: Flags>(names: &[String]) -> Result<F, String>
+where
+    F::Bits: TryFrom<u64>,
+{
+    names.iter().try_fold(F::empty(), |acc, name| {
+        let flag = match name.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16)
+                .ok()
+                .and_then(|bits| F::Bits::try_from(bits).ok())
+                .map(F::from_bits_retain),
+            None => F::from_name(name),
+        };
+        flag.map(|flag| acc.union(flag))
+            .ok_or_else(|| format!("unknown flag {}", name))
+    })
+}
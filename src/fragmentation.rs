@@ -0,0 +1,221 @@
+use crate::bundle::{Bundle, ByteBuffer, FragOffsetType};
+use crate::canonical::{CanonicalBlock, CanonicalData, PAYLOAD_BLOCK, new_canonical_block};
+use crate::flags::{BlockControlFlags, BlockValidation, BundleControlFlags, BundleValidation};
+use crate::helpers::cbor_string_len;
+use crate::primary::PrimaryBlock;
+use thiserror::Error;
+
+/******************************
+ *
+ * Fragmentation (RFC 9171 5.8, 5.9)
+ *
+ ******************************/
+
+#[derive(Error, Debug, PartialEq)]
+pub enum FragmentationError {
+    #[error("bundle must not be fragmented")]
+    MustNotFragment,
+    #[error("maximum payload size must be greater than zero")]
+    InvalidSize,
+    #[error("bundle has no payload block")]
+    NoPayload,
+    #[error("no fragments given")]
+    NoFragments,
+    #[error("bundle {0} is not a fragment")]
+    NotAFragment(String),
+    #[error("fragment {0} does not belong to the same bundle")]
+    Mismatch(String),
+    #[error("fragment {0} has a total application data length of zero")]
+    ZeroTotalLength(String),
+    #[error("fragment {0} exceeds the total application data length")]
+    OutOfBounds(String),
+    #[error("payload incomplete, missing data at offset {0}")]
+    Incomplete(FragOffsetType),
+    #[error("offset {0} is beyond the end of the payload")]
+    InvalidOffset(usize),
+    #[error("fragment size {0} leaves no room for payload")]
+    TooSmall(usize),
+}
+
+/// Splits a bundle into fragments carrying at most `max_payload_len` payload bytes each.
+///
+/// Extension blocks flagged with `BLOCK_REPLICATE` are copied into every fragment, all others
+/// only into the first one. A bundle whose payload already fits is returned unchanged.
+/// Fragments of fragments keep the offsets relative to the original application data unit.
+pub fn fragment(bndl: &Bundle, max_payload_len: usize) -> Result<Vec<Bundle>, FragmentationError> {
+    if max_payload_len == 0 {
+        return Err(FragmentationError::InvalidSize);
+    }
+    let payload = bndl.payload().ok_or(FragmentationError::NoPayload)?;
+    if payload.len() <= max_payload_len {
+        return Ok(vec![bndl.clone()]);
+    }
+    if bndl
+        .primary
+        .bundle_control_flags
+        .contains(BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED)
+    {
+        return Err(FragmentationError::MustNotFragment);
+    }
+
+    let mut fragments = Vec::new();
+    for (i, chunk) in payload.chunks(max_payload_len).enumerate() {
+        let offset = i * max_payload_len;
+        let primary = fragment_primary(bndl, offset);
+        let canonicals = bndl
+            .canonicals
+            .iter()
+            .filter(|b| in_fragment(b, offset))
+            .map(|b| {
+                let mut b = b.clone();
+                if b.block_type == PAYLOAD_BLOCK {
+                    b.set_data(CanonicalData::Data(chunk.to_vec()));
+                }
+                b
+            })
+            .collect();
+
+        let mut frag = Bundle::new(primary, canonicals);
+        frag.calculate_crc();
+        fragments.push(frag);
+    }
+    Ok(fragments)
+}
+
+/// Returns the largest part of the payload starting at `offset` that fits into a fragment
+/// of at most `max_bundle_len` encoded bytes.
+///
+/// The fragment carries the same blocks as the ones created by [`fragment`]. At offset 0 the
+/// whole payload is returned if the bundle fits without being fragmented.
+pub fn fitting_payload(
+    bndl: &Bundle,
+    offset: usize,
+    max_bundle_len: usize,
+) -> Result<&[u8], FragmentationError> {
+    let payload = bndl.payload().ok_or(FragmentationError::NoPayload)?;
+    if offset == 0 && bndl.encoded_len() <= max_bundle_len {
+        return Ok(payload);
+    }
+    if offset >= payload.len() {
+        return Err(FragmentationError::InvalidOffset(offset));
+    }
+    if bndl
+        .primary
+        .bundle_control_flags
+        .contains(BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED)
+    {
+        return Err(FragmentationError::MustNotFragment);
+    }
+
+    // size of the fragment without any payload data, the empty byte string takes one byte
+    let canonicals = bndl
+        .canonicals
+        .iter()
+        .filter(|b| in_fragment(b, offset))
+        .map(|b| {
+            if b.block_type != PAYLOAD_BLOCK {
+                return b.clone();
+            }
+            let mut empty = new_canonical_block(
+                PAYLOAD_BLOCK,
+                b.block_number,
+                b.block_control_flags,
+                CanonicalData::Data(Vec::new()),
+            );
+            empty.crc = b.crc.clone();
+            empty
+        })
+        .collect();
+    let overhead = Bundle::new(fragment_primary(bndl, offset), canonicals).encoded_len() - 1;
+
+    let room = max_bundle_len.saturating_sub(overhead);
+    let mut len = room.saturating_sub(1).min(payload.len() - offset);
+    while len > 0 && cbor_string_len(len) > room {
+        len -= 1;
+    }
+    if len == 0 {
+        return Err(FragmentationError::TooSmall(max_bundle_len));
+    }
+    Ok(&payload[offset..offset + len])
+}
+
+/// Primary block of the fragment starting at `offset` within the payload of `bndl`.
+fn fragment_primary(bndl: &Bundle, offset: usize) -> PrimaryBlock {
+    let mut primary = bndl.primary.clone();
+    if !primary.has_fragmentation() {
+        primary.fragmentation_offset = 0;
+        primary.total_data_length = bndl.payload().map_or(0, |p| p.len() as u64);
+    }
+    primary.bundle_control_flags |= BundleControlFlags::BUNDLE_IS_FRAGMENT.bits();
+    primary.fragmentation_offset += offset as u64;
+    primary
+}
+
+/// Whether block `b` is part of the fragment starting at `offset`.
+fn in_fragment(b: &CanonicalBlock, offset: usize) -> bool {
+    offset == 0
+        || b.block_type == PAYLOAD_BLOCK
+        || b.block_control_flags
+            .contains(BlockControlFlags::BLOCK_REPLICATE)
+}
+
+/// Reassembles the original bundle from its fragments, given in any order.
+///
+/// Overlapping fragments are accepted. Extension blocks are taken from the fragment at offset 0.
+pub fn reassemble(fragments: &[Bundle]) -> Result<Bundle, FragmentationError> {
+    let first = fragments.first().ok_or(FragmentationError::NoFragments)?;
+    let total_len = first.primary.total_data_length;
+
+    let mut sorted: Vec<&Bundle> = Vec::with_capacity(fragments.len());
+    let mut received_len: u64 = 0;
+    for frag in fragments {
+        if !frag.primary.has_fragmentation() {
+            return Err(FragmentationError::NotAFragment(frag.id()));
+        }
+        if frag.primary.source != first.primary.source
+            || frag.primary.creation_timestamp != first.primary.creation_timestamp
+            || frag.primary.total_data_length != total_len
+        {
+            return Err(FragmentationError::Mismatch(frag.id()));
+        }
+        received_len += frag.payload().map_or(0, |p| p.len() as u64);
+        sorted.push(frag);
+    }
+    if total_len == 0 {
+        return Err(FragmentationError::ZeroTotalLength(first.id()));
+    }
+    sorted.sort_by_key(|frag| frag.primary.fragmentation_offset);
+    // extension blocks of the original bundle are taken from the first fragment
+    if sorted[0].primary.fragmentation_offset != 0 {
+        return Err(FragmentationError::Incomplete(0));
+    }
+
+    // the total length is taken from the fragments, do not trust it for allocating
+    let mut data: ByteBuffer = Vec::with_capacity(received_len.min(total_len) as usize);
+    for frag in sorted.iter() {
+        let offset = frag.primary.fragmentation_offset;
+        let payload = frag.payload().ok_or(FragmentationError::NoPayload)?;
+        let end = offset
+            .checked_add(payload.len() as u64)
+            .filter(|end| *end <= total_len)
+            .ok_or_else(|| FragmentationError::OutOfBounds(frag.id()))?;
+        let received = data.len() as u64;
+        if offset > received {
+            return Err(FragmentationError::Incomplete(received));
+        }
+        if end > received {
+            data.extend_from_slice(&payload[(received - offset) as usize..]);
+        }
+    }
+    if (data.len() as u64) < total_len {
+        return Err(FragmentationError::Incomplete(data.len() as u64));
+    }
+
+    let mut bndl = sorted[0].clone();
+    bndl.primary.bundle_control_flags &= !BundleControlFlags::BUNDLE_IS_FRAGMENT.bits();
+    bndl.primary.fragmentation_offset = 0;
+    bndl.primary.total_data_length = 0;
+    bndl.set_payload(data);
+    bndl.calculate_crc();
+    Ok(bndl)
+}
@@ -206,3 +206,182 @@ pub fn bench_bundle_load(runs: i64, crc_type: crc::CrcRawType, mut bundles: Vec<
     let sec = (elapsed.as_secs() as f64) + (f64::from(elapsed.subsec_nanos()) / 1_000_000_000.0);
     println!("{:>15} bundles/second", (runs as f64 / sec) as i64);
 }
+
+/// Maximum nesting of arrays, maps, tags and string chunks rendered by [`cbor_diag`],
+/// the same limit serde_cbor applies when decoding.
+pub const MAX_DIAG_DEPTH: usize = 128;
+
+/// Render a CBOR encoded item in diagnostic notation (RFC 8949, Sec. 8).
+///
+/// Indefinite length items are marked with `_` and integers not using the
+/// preferred (shortest) encoding are annotated with their encoding indicator,
+/// e.g., `23_0`. Returns `None` if the buffer is not a single well-formed item
+/// or nests deeper than [`MAX_DIAG_DEPTH`] levels.
+pub fn cbor_diag(buf: &[u8]) -> Option<String> {
+    let mut out = String::new();
+    let mut pos = 0;
+    diag_item(buf, &mut pos, &mut out, MAX_DIAG_DEPTH)?;
+    if pos != buf.len() {
+        return None;
+    }
+    Some(out)
+}
+
+/// Read the head of a CBOR item: major type, additional information and argument.
+/// The argument is `None` for indefinite lengths.
+fn cbor_head(buf: &[u8], pos: &mut usize) -> Option<(u8, u8, Option<u64>)> {
+    let initial = *buf.get(*pos)?;
+    *pos += 1;
+    let major = initial >> 5;
+    let info = initial & 0x1f;
+    let len = match info {
+        0..=23 => return Some((major, info, Some(info as u64))),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        31 => return Some((major, info, None)),
+        _ => return None,
+    };
+    let bytes = buf.get(*pos..*pos + len)?;
+    *pos += len;
+    let arg = bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+    Some((major, info, Some(arg)))
+}
+
+/// Length of the shortest head of a CBOR item with the given argument.
+pub(crate) fn cbor_head_len(arg: u64) -> usize {
+    match arg {
+        0..=23 => 1,
+        24..=0xff => 2,
+        0x100..=0xffff => 3,
+        0x1_0000..=0xffff_ffff => 5,
+        _ => 9,
+    }
+}
+
+/// Length of a CBOR byte or text string with `len` bytes of content.
+pub(crate) fn cbor_string_len(len: usize) -> usize {
+    cbor_head_len(len as u64) + len
+}
+
+fn encoding_indicator(info: u8, arg: u64) -> &'static str {
+    match info {
+        24 if arg < 24 => "_0",
+        25 if arg <= 0xff => "_1",
+        26 if arg <= 0xffff => "_2",
+        27 if arg <= 0xffff_ffff => "_3",
+        _ => "",
+    }
+}
+
+fn diag_float(f: f64) -> String {
+    if f.is_nan() {
+        "NaN".to_string()
+    } else if f.is_infinite() {
+        if f > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        format!("{:?}", f)
+    }
+}
+
+fn half_to_f64(half: u16) -> f64 {
+    let exp = (half >> 10) & 0x1f;
+    let mant = (half & 0x3ff) as f64;
+    let val = match exp {
+        0 => mant * 2f64.powi(-24),
+        31 if mant == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mant + 1024.0) * 2f64.powi(exp as i32 - 25),
+    };
+    if half & 0x8000 != 0 { -val } else { val }
+}
+
+fn diag_item(buf: &[u8], pos: &mut usize, out: &mut String, depth: usize) -> Option<()> {
+    let depth = depth.checked_sub(1)?;
+    let (major, info, arg) = cbor_head(buf, pos)?;
+    match (major, arg) {
+        (0, Some(n)) => {
+            let _ = write!(out, "{}{}", n, encoding_indicator(info, n));
+        }
+        (1, Some(n)) => {
+            let _ = write!(out, "{}{}", -1 - n as i128, encoding_indicator(info, n));
+        }
+        (2, Some(n)) | (3, Some(n)) => {
+            let end = pos.checked_add(usize::try_from(n).ok()?)?;
+            let data = buf.get(*pos..end)?;
+            *pos = end;
+            if major == 2 {
+                let _ = write!(out, "h'{}'", hexify(data));
+            } else {
+                let text = core::str::from_utf8(data).ok()?;
+                out.push_str(&serde_json::to_string(text).ok()?);
+            }
+        }
+        (2, None) | (3, None) => {
+            out.push_str("(_ ");
+            let mut first = true;
+            while *buf.get(*pos)? != 0xff {
+                if !first {
+                    out.push_str(", ");
+                }
+                first = false;
+                // chunks must be definite strings of the same major type
+                if buf[*pos] >> 5 != major || buf[*pos] & 0x1f == 31 {
+                    return None;
+                }
+                diag_item(buf, pos, out, depth)?;
+            }
+            *pos += 1;
+            out.push(')');
+        }
+        (4, len) | (5, len) => {
+            let (open, close) = if major == 4 { ('[', ']') } else { ('{', '}') };
+            out.push(open);
+            if len.is_none() {
+                out.push_str("_ ");
+            }
+            let mut i = 0;
+            loop {
+                match len {
+                    Some(n) if i == n => break,
+                    None if *buf.get(*pos)? == 0xff => {
+                        *pos += 1;
+                        break;
+                    }
+                    _ => {}
+                }
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                diag_item(buf, pos, out, depth)?;
+                if major == 5 {
+                    out.push_str(": ");
+                    diag_item(buf, pos, out, depth)?;
+                }
+                i += 1;
+            }
+            out.push(close);
+        }
+        (6, Some(tag)) => {
+            let _ = write!(out, "{}(", tag);
+            diag_item(buf, pos, out, depth)?;
+            out.push(')');
+        }
+        (7, Some(val)) => match info {
+            20 => out.push_str("false"),
+            21 => out.push_str("true"),
+            22 => out.push_str("null"),
+            23 => out.push_str("undefined"),
+            0..=19 | 24 => {
+                let _ = write!(out, "simple({})", val);
+            }
+            25 => out.push_str(&diag_float(half_to_f64(val as u16))),
+            26 => out.push_str(&diag_float(f32::from_bits(val as u32) as f64)),
+            27 => out.push_str(&diag_float(f64::from_bits(val))),
+            _ => return None,
+        },
+        _ => return None,
+    }
+    Some(())
+}
@@ -0,0 +1,317 @@
+//! Human-readable JSON representation of bundles.
+//!
+//! The format is described by the JSON Schema in [`JSON_SCHEMA`]. Endpoints are written as
+//! URI strings, flags as lists of names, creation times as RFC 3339 strings and block data
+//! as base64. Fields only present for readability, such as `id` and `type_name`, are ignored
+//! when parsing, just like an optional `report` added by tools.
+
+use crate::bundle::{Bundle, ByteBuffer};
+use crate::canonical::{
+    BUNDLE_AGE_BLOCK, CanonicalBlock, CanonicalBlockType, CanonicalData, HOP_COUNT_BLOCK,
+    PAYLOAD_BLOCK, PREVIOUS_NODE_BLOCK, block_type_name,
+};
+use crate::crc::{CRC_16, CRC_32, CRC_NO, CrcRawType, CrcValue};
+use crate::dtntime::{CreationTimestamp, DtnTime, SECONDS1970_TO2K};
+use crate::eid::EndpointID;
+use crate::error::Error;
+use crate::flags::{BlockControlFlags, BundleControlFlags, flag_names, parse_flag_names};
+use crate::helpers::{hexify, unhexify};
+use crate::primary::PrimaryBlock;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use core::convert::TryFrom;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::UNIX_EPOCH;
+
+/// JSON Schema of the representation produced by [`Bundle::to_json`].
+pub const JSON_SCHEMA: &str = include_str!("../schema/bundle.schema.json");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonBundle {
+    #[serde(default)]
+    pub id: String,
+    pub primary: JsonPrimaryBlock,
+    pub blocks: Vec<JsonCanonicalBlock>,
+    /// Tool specific results, such as the CRC and validation report of `bp7 decode`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonPrimaryBlock {
+    pub flags: Vec<String>,
+    pub crc: JsonCrc,
+    pub destination: String,
+    pub source: String,
+    pub report_to: String,
+    pub creation_timestamp: JsonCreationTimestamp,
+    pub lifetime_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fragment: Option<JsonFragment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonCreationTimestamp {
+    /// RFC 3339 time with millisecond precision, the DTN epoch if the source has no clock.
+    ///
+    /// Times after the year 9999 are written as decimal DTN time in milliseconds.
+    pub time: String,
+    pub seqno: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonFragment {
+    pub offset: u64,
+    pub total_data_length: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonCrc {
+    /// `none`, `crc16`, `crc32` or the decimal code of an unknown CRC type.
+    #[serde(rename = "type")]
+    pub crc_type: String,
+    /// Hex encoded checksum, absent if not calculated yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonCanonicalBlock {
+    pub number: u64,
+    #[serde(rename = "type")]
+    pub block_type: CanonicalBlockType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    pub flags: Vec<String>,
+    pub crc: JsonCrc,
+    /// Depends on the block type: base64 for payload and unknown blocks, an endpoint for
+    /// previous node blocks, `{"limit", "count"}` for hop count and `{"age_ms"}` for bundle
+    /// age blocks.
+    pub data: Value,
+}
+
+fn invalid(msg: impl Into<String>) -> Error {
+    Error::BundleError(msg.into())
+}
+
+fn crc_to_json(crc: &CrcValue) -> JsonCrc {
+    let crc_type = match crc.to_code() {
+        CRC_NO => "none".to_string(),
+        CRC_16 => "crc16".to_string(),
+        CRC_32 => "crc32".to_string(),
+        code => code.to_string(),
+    };
+    let value = match crc {
+        CrcValue::Crc16(buf) => Some(hexify(buf)),
+        CrcValue::Crc32(buf) => Some(hexify(buf)),
+        _ => None,
+    };
+    JsonCrc { crc_type, value }
+}
+
+fn crc_from_json(crc: &JsonCrc) -> Result<CrcValue, Error> {
+    let code: CrcRawType = match crc.crc_type.as_str() {
+        "none" => CRC_NO,
+        "crc16" => CRC_16,
+        "crc32" => CRC_32,
+        other => other
+            .parse()
+            .map_err(|_| invalid(format!("unknown CRC type {}", other)))?,
+    };
+    let value = match &crc.value {
+        Some(hex) => unhexify(hex).map_err(|_| invalid(format!("invalid CRC value {}", hex)))?,
+        None => Vec::new(),
+    };
+    match (code, value.len()) {
+        (CRC_NO, 0) => Ok(CrcValue::CrcNo),
+        (CRC_16, 0) => Ok(CrcValue::Crc16Empty),
+        (CRC_32, 0) => Ok(CrcValue::Crc32Empty),
+        (CRC_16, 2) => Ok(CrcValue::Crc16([value[0], value[1]])),
+        (CRC_32, 4) => Ok(CrcValue::Crc32([value[0], value[1], value[2], value[3]])),
+        (CRC_NO | CRC_16 | CRC_32, _) => Err(invalid(format!(
+            "CRC value length {} does not match type {}",
+            value.len(),
+            crc.crc_type
+        ))),
+        (code, _) => Ok(CrcValue::Unknown(code)),
+    }
+}
+
+/// Last DTN time representable in RFC 3339, 9999-12-31T23:59:59.999Z.
+const MAX_RFC3339_TIME: DtnTime = (253_402_300_800 - SECONDS1970_TO2K) * 1000 - 1;
+
+/// RFC 3339 time, later times are written as decimal DTN time in milliseconds.
+fn time_to_json(time: DtnTime) -> String {
+    if time > MAX_RFC3339_TIME {
+        return time.to_string();
+    }
+    let t = UNIX_EPOCH + Duration::from_secs(SECONDS1970_TO2K) + Duration::from_millis(time);
+    humantime::format_rfc3339_millis(t).to_string()
+}
+
+fn time_from_json(time: &str) -> Result<DtnTime, Error> {
+    if let Ok(time) = time.parse::<DtnTime>() {
+        return Ok(time);
+    }
+    let t = humantime::parse_rfc3339(time)
+        .map_err(|err| invalid(format!("invalid time {}: {}", time, err)))?;
+    t.duration_since(UNIX_EPOCH + Duration::from_secs(SECONDS1970_TO2K))
+        .map(|d| d.as_millis() as DtnTime)
+        .map_err(|_| invalid(format!("time {} is before the DTN epoch", time)))
+}
+
+fn eid_from_json(eid: &str) -> Result<EndpointID, Error> {
+    Ok(EndpointID::try_from(eid)?)
+}
+
+fn base64_from_json(data: &Value) -> Result<ByteBuffer, Error> {
+    data.as_str()
+        .and_then(|s| BASE64.decode(s).ok())
+        .ok_or_else(|| invalid("block data is not valid base64"))
+}
+
+fn data_to_json(data: &CanonicalData) -> Value {
+    match data {
+        CanonicalData::HopCount(limit, count) => json!({ "limit": limit, "count": count }),
+        CanonicalData::Data(buf) | CanonicalData::Unknown(buf) => json!(BASE64.encode(buf)),
+        CanonicalData::BundleAge(age) => json!({ "age_ms": age }),
+        CanonicalData::PreviousNode(eid) => json!(eid.to_string()),
+        CanonicalData::DecodingError => Value::Null,
+    }
+}
+
+fn data_from_json(block_type: CanonicalBlockType, data: &Value) -> Result<CanonicalData, Error> {
+    let field = |name: &str| {
+        data.get(name)
+            .and_then(Value::as_u64)
+            .ok_or_else(|| invalid(format!("missing block data field {}", name)))
+    };
+    Ok(match block_type {
+        PAYLOAD_BLOCK => CanonicalData::Data(base64_from_json(data)?),
+        HOP_COUNT_BLOCK => {
+            let limit =
+                u8::try_from(field("limit")?).map_err(|_| invalid("hop limit too large"))?;
+            let count =
+                u8::try_from(field("count")?).map_err(|_| invalid("hop count too large"))?;
+            CanonicalData::HopCount(limit, count)
+        }
+        BUNDLE_AGE_BLOCK => CanonicalData::BundleAge(field("age_ms")?),
+        PREVIOUS_NODE_BLOCK => CanonicalData::PreviousNode(eid_from_json(
+            data.as_str()
+                .ok_or_else(|| invalid("previous node is not a string"))?,
+        )?),
+        _ => CanonicalData::Unknown(base64_from_json(data)?),
+    })
+}
+
+impl From<&PrimaryBlock> for JsonPrimaryBlock {
+    fn from(primary: &PrimaryBlock) -> Self {
+        JsonPrimaryBlock {
+            flags: flag_names(BundleControlFlags::from_bits_retain(
+                primary.bundle_control_flags,
+            )),
+            crc: crc_to_json(&primary.crc),
+            destination: primary.destination.to_string(),
+            source: primary.source.to_string(),
+            report_to: primary.report_to.to_string(),
+            creation_timestamp: JsonCreationTimestamp {
+                time: time_to_json(primary.creation_timestamp.dtntime()),
+                seqno: primary.creation_timestamp.seqno(),
+            },
+            lifetime_ms: primary.lifetime.as_millis() as u64,
+            fragment: primary.has_fragmentation().then_some(JsonFragment {
+                offset: primary.fragmentation_offset,
+                total_data_length: primary.total_data_length,
+            }),
+        }
+    }
+}
+
+impl TryFrom<&JsonPrimaryBlock> for PrimaryBlock {
+    type Error = Error;
+
+    fn try_from(json: &JsonPrimaryBlock) -> Result<Self, Self::Error> {
+        let mut primary = PrimaryBlock::new();
+        primary.bundle_control_flags = parse_flag_names::<BundleControlFlags>(&json.flags)
+            .map_err(invalid)?
+            .bits();
+        primary.crc = crc_from_json(&json.crc)?;
+        primary.destination = eid_from_json(&json.destination)?;
+        primary.source = eid_from_json(&json.source)?;
+        primary.report_to = eid_from_json(&json.report_to)?;
+        primary.creation_timestamp = CreationTimestamp::with_time_and_seq(
+            time_from_json(&json.creation_timestamp.time)?,
+            json.creation_timestamp.seqno,
+        );
+        primary.lifetime = Duration::from_millis(json.lifetime_ms);
+        if let Some(fragment) = &json.fragment {
+            primary.fragmentation_offset = fragment.offset;
+            primary.total_data_length = fragment.total_data_length;
+        }
+        Ok(primary)
+    }
+}
+
+impl From<&CanonicalBlock> for JsonCanonicalBlock {
+    fn from(cblock: &CanonicalBlock) -> Self {
+        JsonCanonicalBlock {
+            number: cblock.block_number,
+            block_type: cblock.block_type,
+            type_name: block_type_name(cblock.block_type).map(str::to_string),
+            flags: flag_names(BlockControlFlags::from_bits_retain(
+                cblock.block_control_flags,
+            )),
+            crc: crc_to_json(&cblock.crc),
+            data: data_to_json(cblock.data()),
+        }
+    }
+}
+
+impl TryFrom<&JsonCanonicalBlock> for CanonicalBlock {
+    type Error = Error;
+
+    fn try_from(json: &JsonCanonicalBlock) -> Result<Self, Self::Error> {
+        let mut cblock = CanonicalBlock::new();
+        cblock.block_type = json.block_type;
+        cblock.block_number = json.number;
+        cblock.block_control_flags = parse_flag_names::<BlockControlFlags>(&json.flags)
+            .map_err(invalid)?
+            .bits();
+        cblock.crc = crc_from_json(&json.crc)?;
+        cblock.set_data(data_from_json(json.block_type, &json.data)?);
+        Ok(cblock)
+    }
+}
+
+impl From<&Bundle> for JsonBundle {
+    fn from(bndl: &Bundle) -> Self {
+        JsonBundle {
+            id: bndl.id(),
+            primary: (&bndl.primary).into(),
+            blocks: bndl.canonicals.iter().map(Into::into).collect(),
+            report: None,
+        }
+    }
+}
+
+impl TryFrom<&JsonBundle> for Bundle {
+    type Error = Error;
+
+    fn try_from(json: &JsonBundle) -> Result<Self, Self::Error> {
+        Ok(Bundle::new(
+            (&json.primary).try_into()?,
+            json.blocks
+                .iter()
+                .map(CanonicalBlock::try_from)
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
@@ -45,10 +45,17 @@ pub mod crc;
 pub mod dtntime;
 pub mod eid;
 pub mod error;
+// C FFI exports would clash with the wasm-bindgen class exports
+#[cfg(not(target_arch = "wasm32"))]
 pub mod ffi;
 pub mod flags;
+pub mod fragmentation;
 pub mod helpers;
+pub mod json;
+pub mod node;
 pub mod primary;
+#[cfg(feature = "python")]
+pub mod python;
 #[cfg(feature = "bpsec")]
 pub mod security;
 
@@ -60,6 +67,7 @@ pub use canonical::*;
 pub use dtntime::{CreationTimestamp, DtnTime, dtn_time_now};
 pub use eid::EndpointID;
 pub use helpers::hexify;
+pub use node::NodeContext;
 
 #[cfg(target_arch = "wasm32")]
 pub use wasm::*;
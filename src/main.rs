@@ -1,8 +1,12 @@
+use bp7::administrative_record::*;
+use bp7::bundle::Block;
+use bp7::crc::{BlockCrcReport, CrcBlock, CrcStatus, blank_crc};
 use bp7::dtntime::DtnTimeHelpers;
-use bp7::flags::BlockControlFlags;
+use bp7::flags::{BlockControlFlags, BundleControlFlags};
 use bp7::helpers::*;
-use bp7::primary::PrimaryBlock;
+use bp7::json::JsonBundle;
 use bp7::*;
+use serde_json::json;
 use std::convert::TryInto;
 use std::env;
 use std::fs;
@@ -12,9 +16,50 @@ use std::io::prelude::*;
 fn usage(filepath: &str) {
     println!("usage {:?} <cmd> [args]", filepath);
     println!(
-        "\t encode <manifest> <payloadfile | - > [-x] - encode bundle and output raw bytes or hex string (-x)"
+        "\t encode <manifest> <payloadfile | - > [-x] [-s key=value]... - encode bundle and output raw bytes or hex string (-x)"
+    );
+    println!(
+        "\t\t manifest keys: destination, source, report_to, lifetime, flags, status_requests, crc,"
+    );
+    println!(
+        "\t\t creation_time, sequence_number, hop_limit, bundle_age, previous_node, block, bib_key, bib_sha"
+    );
+    println!(
+        "\t decode <hexstring | - > [-p] [--format debug|json|diag|summary] - decode bundle or payload only (-p)"
+    );
+    println!(
+        "\t\t --stream [--src pattern] [--dst pattern] - one line per bundle of concatenated bundles"
+    );
+    println!(
+        "\t validate <file | - > [-k keyfile] - check bundle, CRCs and BIBs (bpsec), exit 1 if invalid"
+    );
+    println!(
+        "\t lint <file | - > [-k keyfile] - validate and report interoperability warnings, exit 2 on warnings"
+    );
+    println!(
+        "\t update <file | - > <node-id> <residence-time> [-x] - update hop count, previous node and bundle age"
+    );
+    println!("\t set-crc <file | - > <no|16|32> [-x] - recalculate all CRCs with the given type");
+    println!("\t strip <file | - > <block-number> [-x] - remove an extension block");
+    println!(
+        "\t sign <file | - > <keyfile> <block-number>[,...] [-x] - add a BIB-HMAC-SHA384 block protecting the given blocks (bpsec)"
+    );
+    println!(
+        "\t fragment <file | - > <max-payload-size> <out-prefix> [-x] - split bundle into <out-prefix>.N files"
+    );
+    println!("\t reassemble <fragment-file>... [-x] - join fragments into the original bundle");
+    println!(
+        "\t split <file | - > <out-prefix> [--src pattern] [--dst pattern] - write each bundle of a stream to <out-prefix>.N"
+    );
+    println!(
+        "\t cat <file | - >... [--src pattern] [--dst pattern] [-x] - concatenate and filter bundle streams"
+    );
+    println!(
+        "\t status-report --for <file | - > --src <eid> --status <received,forwarded,delivered,deleted>"
+    );
+    println!(
+        "\t\t [--reason <code | name>] [--crc no|16|32] [--force] [-x] - create a status report bundle"
     );
-    println!("\t decode <hexstring | - > [-p] - decode bundle or payload only (-p)");
     println!(
         "\t dtntime [dtntimestamp] - prints current time as dtntimestamp or prints dtntime human readable"
     );
@@ -23,51 +68,353 @@ fn usage(filepath: &str) {
     println!("\t benchmark - run a simple benchmark encoding/decoding bundles");
 }
 
-fn manifest_to_primary(manifest: &str) -> PrimaryBlock {
-    let manifest = fs::read(manifest).expect("error reading bundle manifest");
-    let mut primary = bp7::primary::PrimaryBlockBuilder::default()
-        .crc(bp7::crc::CrcValue::CrcNo)
-        .creation_timestamp(bp7::CreationTimestamp::now())
-        .lifetime("1d".parse::<humantime::Duration>().unwrap().into());
+/// Bundle description assembled from a manifest file and `-s key=value` overrides.
+#[derive(Debug, Clone)]
+struct Manifest {
+    destination: Option<EndpointID>,
+    source: Option<EndpointID>,
+    report_to: Option<EndpointID>,
+    lifetime: core::time::Duration,
+    flags: bp7::flags::BundleControlFlagsType,
+    crc: bp7::crc::CrcRawType,
+    creation_time: Option<DtnTime>,
+    sequence_number: Option<u64>,
+    hop_limit: Option<u8>,
+    bundle_age: Option<core::time::Duration>,
+    previous_node: Option<EndpointID>,
+    blocks: Vec<(
+        CanonicalBlockType,
+        bp7::flags::BlockControlFlagsType,
+        Vec<u8>,
+    )>,
+    bib_key: Option<String>,
+    bib_sha: u16,
+}
 
-    for line in String::from_utf8_lossy(&manifest)
-        .split('\n')
-        .map(|f| f.trim())
-        .filter(|l| !l.is_empty() || l.starts_with("^#"))
-        .filter(|l| l.contains('='))
-    {
-        let result: Vec<&str> = line.splitn(2, '=').map(|f| f.trim()).collect();
-        match result[0] {
-            "destination" => {
-                primary = primary.destination(result[1].try_into().unwrap());
+impl Default for Manifest {
+    fn default() -> Self {
+        Manifest {
+            destination: None,
+            source: None,
+            report_to: None,
+            lifetime: core::time::Duration::from_secs(60 * 60 * 24),
+            flags: 0,
+            crc: bp7::crc::CRC_NO,
+            creation_time: None,
+            sequence_number: None,
+            hop_limit: None,
+            bundle_age: None,
+            previous_node: None,
+            blocks: Vec::new(),
+            bib_key: None,
+            bib_sha: 384,
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    let parsed = if let Some(hex) = value.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16)
+            .map_err(|e| e.to_string())
+            .and_then(|v| {
+                v.to_string()
+                    .parse::<T>()
+                    .map_err(|_| "out of range".into())
+            })
+    } else {
+        value.parse::<T>().map_err(|_| "not a valid number".into())
+    };
+    parsed.map_err(|e: String| format!("invalid value for {}: {} ({})", key, value, e))
+}
+
+fn parse_crc(value: &str) -> Result<bp7::crc::CrcRawType, String> {
+    match value {
+        "no" | "none" | "0" => Ok(bp7::crc::CRC_NO),
+        "16" => Ok(bp7::crc::CRC_16),
+        "32" => Ok(bp7::crc::CRC_32),
+        _ => Err(format!("invalid value for crc: {} (no, 16, 32)", value)),
+    }
+}
+
+fn parse_eid(key: &str, value: &str) -> Result<EndpointID, String> {
+    EndpointID::try_from(value).map_err(|e| format!("invalid value for {}: {}", key, e))
+}
+
+fn parse_duration(key: &str, value: &str) -> Result<core::time::Duration, String> {
+    value
+        .parse::<humantime::Duration>()
+        .map(|d| d.into())
+        .map_err(|e| format!("invalid value for {}: {}", key, e))
+}
+
+impl Manifest {
+    fn from_file(path: &str) -> Result<Manifest, String> {
+        let content = fs::read(path).map_err(|e| format!("error reading {}: {}", path, e))?;
+        let mut manifest = Manifest::default();
+        for (num, line) in String::from_utf8_lossy(&content).lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
             }
-            "source" => {
-                primary = primary.source(result[1].try_into().unwrap());
+            manifest
+                .set_line(line)
+                .map_err(|e| format!("{}:{}: {}", path, num + 1, e))?;
+        }
+        Ok(manifest)
+    }
+
+    fn set_line(&mut self, line: &str) -> Result<(), String> {
+        match line.split_once('=') {
+            Some((key, value)) => self.set(key.trim(), value.trim()),
+            None => Err(format!("expected key=value, got: {}", line)),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "destination" => self.destination = Some(parse_eid(key, value)?),
+            "source" => self.source = Some(parse_eid(key, value)?),
+            "report_to" => self.report_to = Some(parse_eid(key, value)?),
+            "lifetime" => self.lifetime = parse_duration(key, value)?,
+            "flags" => self.flags = parse_number(key, value)?,
+            "status_requests" => {
+                for status in value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+                    let flag = match status {
+                        "received" => BundleControlFlags::BUNDLE_STATUS_REQUEST_RECEPTION,
+                        "forwarded" => BundleControlFlags::BUNDLE_STATUS_REQUEST_FORWARD,
+                        "delivered" => BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY,
+                        "deleted" => BundleControlFlags::BUNDLE_STATUS_REQUEST_DELETION,
+                        _ => return Err(format!("unknown status request: {}", status)),
+                    };
+                    self.flags |= flag.bits();
+                }
             }
-            "report_to" => {
-                primary = primary.report_to(result[1].try_into().unwrap());
+            "crc" => self.crc = parse_crc(value)?,
+            "creation_time" => {
+                self.creation_time = if value == "now" {
+                    None
+                } else {
+                    Some(parse_number(key, value)?)
+                }
             }
-            "lifetime" => {
-                primary =
-                    primary.lifetime(result[1].parse::<humantime::Duration>().unwrap().into());
+            "sequence_number" => self.sequence_number = Some(parse_number(key, value)?),
+            "hop_limit" => {
+                let limit: u8 = parse_number(key, value)?;
+                if limit == 0 {
+                    return Err("hop_limit must be between 1 and 255".into());
+                }
+                self.hop_limit = Some(limit);
             }
-            "flags" => {
-                primary = primary.bundle_control_flags(result[1].parse().unwrap());
+            "bundle_age" => self.bundle_age = Some(parse_duration(key, value)?),
+            "previous_node" => self.previous_node = Some(parse_eid(key, value)?),
+            "block" => {
+                let parts: Vec<&str> = value.split(':').map(|p| p.trim()).collect();
+                let (block_type, flags, data) = match parts[..] {
+                    [t, d] => (parse_number(key, t)?, 0, d),
+                    [t, f, d] => (parse_number(key, t)?, parse_number(key, f)?, d),
+                    _ => return Err(format!("expected block = type[:flags]:hex, got: {}", value)),
+                };
+                if matches!(
+                    block_type,
+                    PAYLOAD_BLOCK | HOP_COUNT_BLOCK | BUNDLE_AGE_BLOCK | PREVIOUS_NODE_BLOCK
+                ) {
+                    return Err(format!(
+                        "block type {} must be set via its dedicated key",
+                        block_type
+                    ));
+                }
+                let data = unhexify(data).map_err(|e| format!("invalid block data: {}", e))?;
+                self.blocks.push((block_type, flags, data));
             }
-            _ => {
-                eprintln!("unknown key: {}", result[0]);
+            "bib_key" => self.bib_key = Some(value.to_string()),
+            "bib_sha" => {
+                self.bib_sha = match value {
+                    "256" => 256,
+                    "384" => 384,
+                    "512" => 512,
+                    _ => {
+                        return Err(format!(
+                            "invalid value for bib_sha: {} (256, 384, 512)",
+                            value
+                        ));
+                    }
+                }
             }
+            _ => return Err(format!("unknown key: {}", key)),
+        }
+        Ok(())
+    }
+
+    fn build_bundle(&self, payload: Vec<u8>) -> Result<Bundle, String> {
+        let creation_timestamp = match (self.creation_time, self.sequence_number) {
+            (None, None) => bp7::CreationTimestamp::now(),
+            (None, Some(seq)) => bp7::CreationTimestamp::with_time_and_seq(dtn_time_now(), seq),
+            (Some(t), seq) => bp7::CreationTimestamp::with_time_and_seq(t, seq.unwrap_or(0)),
+        };
+        let mut primary = bp7::primary::PrimaryBlockBuilder::default()
+            .bundle_control_flags(self.flags)
+            .destination(self.destination.clone().ok_or("missing key: destination")?)
+            .creation_timestamp(creation_timestamp)
+            .lifetime(self.lifetime);
+        if let Some(source) = &self.source {
+            primary = primary.source(source.clone());
+        }
+        if let Some(report_to) = &self.report_to {
+            primary = primary.report_to(report_to.clone());
+        }
+        let primary = primary.build().map_err(|e| e.to_string())?;
+
+        let mut b = bundle::Bundle::new(
+            primary,
+            vec![bp7::new_payload_block(BlockControlFlags::empty(), payload)],
+        );
+        if let Some(limit) = self.hop_limit {
+            b.add_canonical_block(new_hop_count_block(0, BlockControlFlags::empty(), limit));
+        }
+        if let Some(age) = self.bundle_age {
+            b.add_canonical_block(new_bundle_age_block(
+                0,
+                BlockControlFlags::empty(),
+                age.as_millis() as u64,
+            ));
+        }
+        if let Some(node) = &self.previous_node {
+            b.add_canonical_block(new_previous_node_block(
+                0,
+                BlockControlFlags::empty(),
+                node.clone(),
+            ));
         }
+        for (block_type, flags, data) in &self.blocks {
+            b.add_canonical_block(new_canonical_block(
+                *block_type,
+                0,
+                *flags,
+                CanonicalData::Unknown(data.clone()),
+            ));
+        }
+        b.ensure_bundle_age_block();
+        b.set_crc(self.crc);
+        b.calculate_crc();
+
+        if let Some(key_file) = &self.bib_key {
+            let payload_block = b
+                .extension_block_by_type(PAYLOAD_BLOCK)
+                .ok_or("bundle has no payload block")?
+                .block_number;
+            sign_blocks(&mut b, &read_key(key_file)?, self.bib_sha, &[payload_block])?;
+        }
+        b.validate().map_err(|errs| {
+            format!(
+                "created an invalid bundle: {}",
+                errs.iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+        Ok(b)
+    }
+}
+
+/// Reads a 128 bit BIB key either as raw bytes or as a hex string.
+fn read_key(path: &str) -> Result<[u8; 16], String> {
+    let content = fs::read(path).map_err(|e| format!("error reading {}: {}", path, e))?;
+    let key = if content.len() == 16 {
+        content
+    } else {
+        unhexify(String::from_utf8_lossy(&content).trim())
+            .map_err(|e| format!("invalid key in {}: {}", path, e))?
+    };
+    key.try_into()
+        .map_err(|_| format!("key in {} must be exactly 16 bytes", path))
+}
+
+/// Adds a BIB-HMAC-SHA2 integrity block protecting the given target blocks.
+#[cfg(feature = "bpsec")]
+fn sign_blocks(b: &mut Bundle, key: &[u8; 16], sha: u16, targets: &[u64]) -> Result<(), String> {
+    use bp7::security::*;
+
+    let sha_variant = match sha {
+        256 => HMAC_SHA_256,
+        512 => HMAC_SHA_512,
+        _ => HMAC_SHA_384,
+    };
+    let block_number = b
+        .canonicals
+        .iter()
+        .map(|c| c.block_number)
+        .max()
+        .unwrap_or(1)
+        + 1;
+    let scope_flags = 0x0007;
+
+    let mut ippts = Vec::with_capacity(targets.len());
+    for target in targets {
+        let target_block = b
+            .block(*target)
+            .ok_or_else(|| format!("no block with number {} in bundle", target))?;
+        let mut ippt = IpptBuilder::default()
+            .primary_block(b.primary.clone())
+            .security_header((
+                INTEGRITY_BLOCK,
+                block_number,
+                BlockControlFlags::empty().bits(),
+            ))
+            .scope_flags(scope_flags)
+            .build();
+        ippts.push((*target, ippt.create(target_block)));
+    }
+
+    let mut bib = IntegrityBlockBuilder::default()
+        .security_targets(targets.to_vec())
+        .security_context_flags(SEC_CONTEXT_PRESENT)
+        .security_source(b.primary.source.clone())
+        .security_context_parameters(BibSecurityContextParameter::new(
+            Some((1, sha_variant)),
+            None,
+            Some((3, scope_flags)),
+        ))
+        .build()
+        .map_err(|e| e.to_string())?;
+    bib.compute_hmac(*key, ippts.iter().map(|(t, ippt)| (*t, ippt)).collect());
+
+    // the block number is part of the signed data, so it must be the one assigned on insertion
+    let assigned = b
+        .add_block(new_integrity_block(
+            block_number,
+            BlockControlFlags::empty(),
+            bib.to_cbor(),
+        ))
+        .map_err(|e| e.to_string())?;
+    if assigned != block_number {
+        return Err(format!(
+            "BIB was assigned block number {} instead of {}",
+            assigned, block_number
+        ));
     }
-    primary.build().unwrap()
+    Ok(())
 }
-fn generate_bundle(primary_block: PrimaryBlock, payload: Vec<u8>, hex: bool) {
-    let payload_block = bp7::new_payload_block(BlockControlFlags::empty(), payload);
 
-    let mut b = bundle::Bundle::new(primary_block, vec![payload_block]);
+#[cfg(not(feature = "bpsec"))]
+fn sign_blocks(
+    _b: &mut Bundle,
+    _key: &[u8; 16],
+    _sha: u16,
+    _targets: &[u64],
+) -> Result<(), String> {
+    Err("signing requires bp7 to be built with the bpsec feature".into())
+}
+
+fn encode(manifest: &Manifest, payload: Vec<u8>, hex: bool) {
+    let mut b = manifest.build_bundle(payload).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    write_bundle(&mut b, hex);
+}
 
-    b.set_crc(bp7::crc::CRC_NO);
-    b.validate().expect("created in invalid bundle");
+fn write_bundle(b: &mut Bundle, hex: bool) {
     let cbor = b.to_cbor();
 
     if hex {
@@ -77,38 +424,642 @@ fn generate_bundle(primary_block: PrimaryBlock, payload: Vec<u8>, hex: bool) {
     }
 }
 
-fn encode(manifest: &str, data: &str, hex: bool) {
-    let primary_block = manifest_to_primary(manifest);
+fn exit_with(err: impl std::fmt::Display) -> ! {
+    eprintln!("{}", err);
+    std::process::exit(1);
+}
 
-    let payload = fs::read(data).expect("error reading bundle payload");
-    generate_bundle(primary_block, payload, hex);
+/// Reads and decodes a bundle, exiting on errors.
+fn load_bundle(path: &str) -> Bundle {
+    let buf = read_bundle_input(path).unwrap_or_else(|err| exit_with(err));
+    buf.try_into()
+        .unwrap_or_else(|err| exit_with(format!("error decoding bundle {}: {}", path, err)))
 }
 
-fn encode_from_stdin(manifest: &str, hex: bool) {
-    let primary_block = manifest_to_primary(manifest);
-    let mut payload: Vec<u8> = Vec::new();
-    io::stdin()
-        .read_to_end(&mut payload)
-        .expect("Error reading from stdin.");
-    generate_bundle(primary_block, payload, hex);
+/// Reads and decodes a bundle keeping its encoding, so unchanged blocks are written as received.
+fn load_bundle_preserving(path: &str) -> Bundle {
+    let buf = read_bundle_input(path).unwrap_or_else(|err| exit_with(err));
+    Bundle::from_cbor_preserving(&buf)
+        .unwrap_or_else(|err| exit_with(format!("error decoding bundle {}: {}", path, err)))
+}
+
+/// Generates a status report bundle for a given bundle.
+fn status_report(args: &[String]) -> Result<(), String> {
+    let mut orig = None;
+    let mut src = None;
+    let mut statuses = Vec::new();
+    let mut reason = NO_INFORMATION;
+    let mut crc = bp7::crc::CRC_NO;
+    let (mut force, mut hex) = (false, false);
+
+    let mut opts = args.iter();
+    while let Some(opt) = opts.next() {
+        let mut value = || {
+            opts.next()
+                .map(|v| v.as_str())
+                .ok_or_else(|| format!("{} requires a value", opt))
+        };
+        match opt.as_str() {
+            "--for" => orig = Some(load_bundle(value()?)),
+            "--src" => src = Some(parse_eid("src", value()?)?),
+            "--status" => {
+                for name in value()?.split(',') {
+                    statuses.push(
+                        status_information_from_name(name.trim())
+                            .ok_or_else(|| format!("unknown status: {}", name))?,
+                    );
+                }
+            }
+            "--reason" => {
+                let v = value()?;
+                reason = status_report_reason_from_name(v)
+                    .map_or_else(|| parse_number("reason", v), Ok)?;
+            }
+            "--crc" => crc = parse_crc(value()?)?,
+            "--force" => force = true,
+            "-x" => hex = true,
+            _ => return Err(format!("unknown option: {}", opt)),
+        }
+    }
+    let orig = orig.ok_or("missing --for <bundle>")?;
+    let src = src.ok_or("missing --src <eid>")?;
+    if statuses.is_empty() {
+        return Err("missing --status".into());
+    }
+
+    if !force {
+        for status in statuses.iter().filter(|s| !is_status_requested(&orig, **s)) {
+            eprintln!(
+                "warning: {} status not requested by {}, dropped (use --force to include)",
+                status_information_name(*status).unwrap_or_default(),
+                orig.id()
+            );
+        }
+    }
+    let mut report = status_report_bundle_with(&orig, src, crc, &statuses, reason, force)
+        .map_err(|e| e.to_string())?;
+    write_bundle(&mut report, hex);
+    Ok(())
+}
+
+/// Runs one of the bundle transformation subcommands.
+fn transform(cmd: &str, args: &[String]) -> Result<(), String> {
+    let hex = args.iter().any(|a| a == "-x");
+    let args: Vec<&str> = args
+        .iter()
+        .map(|a| a.as_str())
+        .filter(|a| *a != "-x")
+        .collect();
+    match (cmd, &args[..]) {
+        ("update", [input, node, residence_time]) => {
+            let mut b = load_bundle_preserving(input);
+            let node = parse_eid("node", node)?;
+            let residence_time = parse_duration("residence time", residence_time)?;
+            if !b.update_extensions(node, residence_time) {
+                eprintln!("warning: hop limit or lifetime of {} exceeded", b.id());
+            }
+            write_bundle(&mut b, hex);
+        }
+        ("set-crc", [input, crc]) => {
+            let mut b = load_bundle(input);
+            b.set_crc(parse_crc(crc)?);
+            write_bundle(&mut b, hex);
+        }
+        ("strip", [input, block_number]) => {
+            let mut b = load_bundle_preserving(input);
+            let block_number = parse_number("block number", block_number)?;
+            b.remove_block(block_number).ok_or_else(|| {
+                format!("no removable block with number {} in bundle", block_number)
+            })?;
+            write_bundle(&mut b, hex);
+        }
+        ("sign", [input, key_file, targets]) => {
+            let mut b = load_bundle_preserving(input);
+            let key = read_key(key_file)?;
+            let targets = targets
+                .split(',')
+                .map(|t| parse_number("target block number", t))
+                .collect::<Result<Vec<u64>, String>>()?;
+            sign_blocks(&mut b, &key, 384, &targets)?;
+            write_bundle(&mut b, hex);
+        }
+        ("fragment", [input, max_payload_len, prefix]) => {
+            let b = load_bundle(input);
+            let max_payload_len = parse_number("max payload size", max_payload_len)?;
+            let fragments =
+                bp7::fragmentation::fragment(&b, max_payload_len).map_err(|e| e.to_string())?;
+            for (i, mut frag) in fragments.into_iter().enumerate() {
+                let path = format!("{}.{}", prefix, i);
+                let cbor = frag.to_cbor();
+                let content = if hex {
+                    hexify(&cbor).into_bytes()
+                } else {
+                    cbor
+                };
+                fs::write(&path, content).map_err(|e| format!("error writing {}: {}", path, e))?;
+                println!("{}", path);
+            }
+        }
+        ("reassemble", inputs) if !inputs.is_empty() => {
+            let fragments: Vec<Bundle> = inputs.iter().map(|f| load_bundle(f)).collect();
+            let mut b = bp7::fragmentation::reassemble(&fragments).map_err(|e| e.to_string())?;
+            write_bundle(&mut b, hex);
+        }
+        _ => {
+            return Err(format!(
+                "invalid arguments for {}, run without arguments for usage",
+                cmd
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reads a bundle from a file or stdin (`-`), accepting raw CBOR as well as hex strings.
+fn read_bundle_input(path: &str) -> Result<Vec<u8>, String> {
+    let mut buf: Vec<u8> = Vec::new();
+    if path == "-" {
+        io::stdin()
+            .read_to_end(&mut buf)
+            .map_err(|e| format!("error reading from stdin: {}", e))?;
+    } else {
+        buf = fs::read(path).map_err(|e| format!("error reading {}: {}", path, e))?;
+    }
+    // a CBOR encoded bundle always starts with a non-ASCII array header
+    if !buf.is_empty()
+        && buf
+            .iter()
+            .all(|c| c.is_ascii_hexdigit() || c.is_ascii_whitespace())
+    {
+        let hex: String = String::from_utf8_lossy(&buf).split_whitespace().collect();
+        buf = unhexify(&hex).map_err(|e| format!("invalid hex input: {}", e))?;
+    }
+    Ok(buf)
+}
+
+/// Errors and interoperability warnings found while checking a bundle.
+#[derive(Debug, Default)]
+struct Findings {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+#[cfg(feature = "bpsec")]
+fn check_bibs(bndl: &Bundle, key: Option<&[u8; 16]>, findings: &mut Findings) {
+    use bp7::security::{INTEGRITY_BLOCK, IntegrityBlock};
+
+    for b in bndl
+        .canonicals
+        .iter()
+        .filter(|b| b.block_type == INTEGRITY_BLOCK)
+    {
+        let bib = match b.data() {
+            CanonicalData::Unknown(data) => IntegrityBlock::from_cbor(data),
+            _ => continue,
+        };
+        match (bib, key) {
+            (Err(err), _) => findings
+                .errors
+                .push(format!("BIB in block {}: {}", b.block_number, err)),
+            (Ok(_), None) => findings.warnings.push(format!(
+                "BIB in block {} not verified, no key given",
+                b.block_number
+            )),
+            (Ok(bib), Some(key)) => match bib.verify(bndl, b.block_number, *key) {
+                Ok(true) => {}
+                Ok(false) => findings.errors.push(format!(
+                    "BIB in block {}: integrity check failed for targets {:?}",
+                    b.block_number, bib.security_targets
+                )),
+                Err(err) => findings
+                    .errors
+                    .push(format!("BIB in block {}: {}", b.block_number, err)),
+            },
+        }
+    }
+}
+
+#[cfg(not(feature = "bpsec"))]
+fn check_bibs(bndl: &Bundle, _key: Option<&[u8; 16]>, findings: &mut Findings) {
+    for b in bndl
+        .canonicals
+        .iter()
+        .filter(|b| b.block_type == INTEGRITY_BLOCK)
+    {
+        findings.warnings.push(format!(
+            "BIB in block {} not verified, bp7 built without bpsec feature",
+            b.block_number
+        ));
+    }
+}
+
+/// Compares `buf` against the canonical re-encoding of `bndl`, ignoring CRC values.
+///
+/// Blocks with an unsupported CRC type cannot be re-encoded and are compared as received.
+fn lint_encoding(buf: &[u8], bndl: &Bundle, findings: &mut Findings) {
+    let mut received = buf.to_vec();
+    let mut blocks: Vec<(usize, Vec<u8>)> = Vec::new();
+    for (i, report) in bndl.crc_report().into_iter().enumerate() {
+        let Some(span) = report.span else {
+            return;
+        };
+        let raw = &buf[span.clone()];
+        if let CrcStatus::Unsupported(code) = report.status {
+            findings.warnings.push(format!(
+                "block {} uses unsupported CRC type {}",
+                report.block_number, code
+            ));
+            blocks.push((span.start, raw.to_vec()));
+        } else {
+            received[span.clone()].copy_from_slice(&blank_crc(report.crc_type, raw));
+            let encoded = match i {
+                0 => blank_encoding(&bndl.primary),
+                _ => blank_encoding(&bndl.canonicals[i - 1]),
+            };
+            blocks.push((span.start, encoded));
+        }
+    }
+    blocks.sort_by_key(|(start, _)| *start);
+    let mut reencoded = vec![0x9f];
+    for (_, encoded) in blocks {
+        reencoded.extend(encoded);
+    }
+    reencoded.push(0xff);
+    if reencoded != received {
+        let offset = received
+            .iter()
+            .zip(reencoded.iter())
+            .position(|(a, b)| a != b)
+            .unwrap_or(received.len().min(reencoded.len()));
+        findings.warnings.push(format!(
+            "non-canonical CBOR encoding, first difference at byte {}",
+            offset
+        ));
+    }
+}
+
+/// Encodes a block with a zeroed CRC value.
+fn blank_encoding<B: Block + CrcBlock + Clone>(block: &B) -> Vec<u8> {
+    let mut block = block.clone();
+    block.set_crc_type(block.crc_type());
+    block.to_cbor()
+}
+
+fn lint_bundle(buf: &[u8], bndl: &Bundle, findings: &mut Findings) {
+    lint_encoding(buf, bndl, findings);
+    if bndl.extension_block_by_type(HOP_COUNT_BLOCK).is_none() {
+        findings.warnings.push("no hop count block".into());
+    }
+    // RFC 9171 4.3.1: the primary block needs a CRC unless it is protected by a BIB
+    if !bndl.primary.has_crc() && bndl.extension_block_by_type(INTEGRITY_BLOCK).is_none() {
+        findings
+            .warnings
+            .push("primary block has neither a CRC nor a BIB".into());
+    }
+    match bndl.age() {
+        None => findings
+            .warnings
+            .push("bundle age unknown, creation time is 0 and no bundle age block".into()),
+        Some(_) if bndl.is_lifetime_exceeded() => {
+            findings.warnings.push("lifetime already expired".into())
+        }
+        Some(_) => {}
+    }
+    let primary = &bndl.primary;
+    if primary.has_fragmentation() {
+        let payload_len = bndl.payload().map_or(0, |p| p.len() as u64);
+        if primary.total_data_length == 0 {
+            findings
+                .warnings
+                .push("fragment without total_data_length".into());
+        } else if primary.fragmentation_offset + payload_len > primary.total_data_length {
+            findings.warnings.push(format!(
+                "fragment exceeds total_data_length ({} + {} > {})",
+                primary.fragmentation_offset, payload_len, primary.total_data_length
+            ));
+        }
+    }
+}
+
+/// Checks a bundle including its CRCs and BIBs and returns the process exit code.
+///
+/// `validate` exits with 1 on errors, `lint` additionally exits with 2 if only warnings were found.
+fn check_bundle(path: &str, key: Option<[u8; 16]>, lint: bool) -> i32 {
+    let buf = match read_bundle_input(path) {
+        Ok(buf) => buf,
+        Err(err) => {
+            eprintln!("{}", err);
+            return 1;
+        }
+    };
+    let bndl: Bundle = match buf.clone().try_into() {
+        Ok(bndl) => bndl,
+        Err(err) => {
+            println!("error: decoding bundle failed: {}", err);
+            return 1;
+        }
+    };
+    let mut findings = Findings::default();
+    if let Err(errs) = bndl.validate() {
+        findings.errors.extend(errs.iter().map(|e| e.to_string()));
+    }
+    check_bibs(&bndl, key.as_ref(), &mut findings);
+    if lint {
+        lint_bundle(&buf, &bndl, &mut findings);
+    }
+
+    for err in findings.errors.iter() {
+        println!("error: {}", err);
+    }
+    for warning in findings.warnings.iter() {
+        println!("warning: {}", warning);
+    }
+    println!(
+        "{}: {} error(s), {} warning(s)",
+        bndl.id(),
+        findings.errors.len(),
+        findings.warnings.len()
+    );
+    if !findings.errors.is_empty() {
+        1
+    } else if lint && !findings.warnings.is_empty() {
+        2
+    } else {
+        0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DecodeFormat {
+    Debug,
+    Json,
+    Diag,
+    Summary,
+}
+
+impl std::str::FromStr for DecodeFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "debug" => Ok(DecodeFormat::Debug),
+            "json" => Ok(DecodeFormat::Json),
+            "diag" => Ok(DecodeFormat::Diag),
+            "summary" => Ok(DecodeFormat::Summary),
+            _ => Err(format!("unknown output format: {}", s)),
+        }
+    }
 }
 
-fn decode(bundle: &str, payload_only: bool) {
+fn decode(bundle: &str, payload_only: bool, format: DecodeFormat) {
     let buf = unhexify(bundle).unwrap();
     //println!("decode: {:02x?}", &buf);
-    buf_to_bundle(buf, payload_only);
+    buf_to_bundle(buf, payload_only, format);
 }
-fn decode_from_stdin(payload_only: bool) {
+fn decode_from_stdin(payload_only: bool, format: DecodeFormat) {
     let mut buf: Vec<u8> = Vec::new();
     io::stdin()
         .read_to_end(&mut buf)
         .expect("Error reading from stdin.");
     //println!("decode: {:02x?}", &buf);
     //serde_cbor::from_slice::<serde_cbor::Value>(&buf).unwrap();
-    buf_to_bundle(buf, payload_only);
+    buf_to_bundle(buf, payload_only, format);
+}
+
+fn crc_report_to_json(report: &BlockCrcReport) -> serde_json::Value {
+    let status = match report.status {
+        CrcStatus::Absent => "absent",
+        CrcStatus::Valid => "valid",
+        CrcStatus::Invalid => "invalid",
+        CrcStatus::Unsupported(_) => "unsupported",
+    };
+    json!({
+        "number": report.block_number,
+        "status": status,
+        "span": report.span.as_ref().map(|span| [span.start, span.end]),
+    })
+}
+
+fn status_report_to_json(sr: &StatusReport) -> serde_json::Value {
+    let status: Vec<serde_json::Value> = sr
+        .status_information
+        .iter()
+        .enumerate()
+        .map(|(pos, item)| {
+            json!({
+                "name": status_information_name(pos as StatusInformationPos),
+                "asserted": item.asserted,
+                "time": (item.asserted && item.status_requested).then(|| item.time.string()),
+            })
+        })
+        .collect();
+    json!({
+        "type": "status_report",
+        "status": status,
+        "reason": sr.report_reason,
+        "reason_name": status_report_reason_name(sr.report_reason),
+        "refbundle": sr.refbundle(),
+        "source": sr.source_node.to_string(),
+        "creation_timestamp": {
+            "dtntime": sr.timestamp.dtntime(),
+            "seqno": sr.timestamp.seqno(),
+        },
+        "fragment": (sr.frag_len > 0).then(|| json!({
+            "offset": sr.frag_offset,
+            "length": sr.frag_len,
+        })),
+    })
+}
+
+fn admin_record_to_json(record: &AdministrativeRecord) -> serde_json::Value {
+    let mut value = match record {
+        AdministrativeRecord::BundleStatusReport(sr) => status_report_to_json(sr),
+        AdministrativeRecord::BibeProtocolDataUnit(bpdu) => json!({
+            "type": "bibe_pdu",
+            "transmission_id": bpdu.transmission_id,
+            "retransmission_time": bpdu.retransmission_time,
+            "encapsulated_bundle": bpdu.bundle().ok().map(|b| b.id()),
+            "size": bpdu.encapsulated_bundle.len(),
+        }),
+        AdministrativeRecord::CustodySignal(cs) => json!({
+            "type": "custody_signal",
+            "dispositions": cs.dispositions.iter().map(|(disposition, ranges)| json!({
+                "disposition": disposition,
+                "ranges": ranges.iter().map(|r| [r.start(), r.count()]).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+        }),
+        AdministrativeRecord::Unknown(_, data) => json!({ "type": "unknown", "hex": hexify(data) }),
+        AdministrativeRecord::Mismatched(_, data) => {
+            json!({ "type": "mismatched", "hex": hexify(data) })
+        }
+    };
+    value["type_code"] = json!(record.type_code());
+    value
+}
+
+/// Decoded administrative record of the bundle, `None` for regular bundles.
+fn admin_record_of(bndl: &Bundle) -> Option<Result<AdministrativeRecord, String>> {
+    bndl.is_administrative_record()
+        .then(|| bndl.administrative_record().map_err(|e| e.to_string()))
+}
+
+/// JSON representation of a decoded bundle, see [`bp7::json`], with the CRC and validation
+/// results as `report`.
+fn bundle_to_json(bndl: &Bundle) -> JsonBundle {
+    let errors: Vec<String> = match bndl.validate() {
+        Ok(()) => Vec::new(),
+        Err(errs) => errs.iter().map(|e| e.to_string()).collect(),
+    };
+    let crc: Vec<serde_json::Value> = bndl.crc_report().iter().map(crc_report_to_json).collect();
+    let mut json = JsonBundle::from(bndl);
+    json.report = Some(json!({
+        "valid": errors.is_empty(),
+        "errors": errors,
+        "crc": crc,
+        "administrative_record": match admin_record_of(bndl) {
+            Some(Ok(record)) => admin_record_to_json(&record),
+            Some(Err(err)) => json!({ "error": err }),
+            None => serde_json::Value::Null,
+        },
+    }));
+    json
 }
-fn buf_to_bundle(buf: Vec<u8>, payload_only: bool) {
-    let mut bndl: Bundle = buf.try_into().expect("Error decoding bundle!");
+
+fn bundle_summary(bndl: &Bundle) -> String {
+    format!("{:#}", bndl.summary())
+}
+
+/// Matches `text` against a pattern where `*` matches any sequence and `?` any single character.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let (p, t): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+    let (mut pi, mut ti) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star, matched)) = backtrack {
+            pi = star + 1;
+            ti = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+/// Source and destination EID patterns selecting bundles of a stream.
+#[derive(Debug, Default)]
+struct StreamFilter {
+    src: Option<String>,
+    dst: Option<String>,
+}
+
+impl StreamFilter {
+    /// Consumes a `--src` or `--dst` option, returns false for any other option.
+    fn parse_opt<'a>(&mut self, opt: &str, opts: &mut impl Iterator<Item = &'a String>) -> bool {
+        let target = match opt {
+            "--src" => &mut self.src,
+            "--dst" => &mut self.dst,
+            _ => return false,
+        };
+        *target = Some(
+            opts.next()
+                .unwrap_or_else(|| exit_with(format!("{} requires an EID pattern", opt)))
+                .clone(),
+        );
+        true
+    }
+
+    fn matches(&self, bndl: &Bundle) -> bool {
+        self.src
+            .as_ref()
+            .is_none_or(|p| glob_match(p, &bndl.primary.source.to_string()))
+            && self
+                .dst
+                .as_ref()
+                .is_none_or(|p| glob_match(p, &bndl.primary.destination.to_string()))
+    }
+}
+
+fn stream_summary(bndl: &Bundle, size: usize) -> String {
+    let summary = bndl.summary();
+    let mut line = format!(
+        "{} -> {} {} bytes {}",
+        summary.id,
+        summary.destination,
+        size,
+        summary.lifetime_status()
+    );
+    if let Some(record) = &summary.administrative_record {
+        line = format!("{} [{}]", line, record);
+    }
+    line
+}
+
+/// Calls `f` for every bundle of the stream passing the filter, exits after a decoding error.
+fn for_each_bundle(buf: &[u8], filter: &StreamFilter, mut f: impl FnMut(Bundle, &[u8])) {
+    let mut stream = bundle::BundleStream::new(buf);
+    while let Some(next) = stream.next() {
+        match next {
+            Ok((bndl, raw)) if filter.matches(&bndl) => f(bndl, raw),
+            Ok(_) => {}
+            Err(err) => exit_with(format!(
+                "error decoding bundle at offset {}: {}",
+                stream.offset(),
+                err
+            )),
+        }
+    }
+}
+
+fn decode_stream(buf: &[u8], format: DecodeFormat, filter: &StreamFilter) {
+    for_each_bundle(buf, filter, |bndl, raw| match format {
+        DecodeFormat::Json => {
+            println!("{}", serde_json::to_string(&bundle_to_json(&bndl)).unwrap())
+        }
+        DecodeFormat::Diag => println!("{}", cbor_diag(raw).unwrap_or_default()),
+        DecodeFormat::Debug | DecodeFormat::Summary => {
+            println!("{}", stream_summary(&bndl, raw.len()))
+        }
+    });
+}
+
+/// Writes every bundle of the given stream files to `<prefix>.N`.
+fn split(input: &str, prefix: &str, filter: &StreamFilter) {
+    let buf = read_bundle_input(input).unwrap_or_else(|err| exit_with(err));
+    let mut count = 0;
+    for_each_bundle(&buf, filter, |bndl, raw| {
+        let path = format!("{}.{}", prefix, count);
+        fs::write(&path, raw)
+            .unwrap_or_else(|e| exit_with(format!("error writing {}: {}", path, e)));
+        println!("{}: {}", path, stream_summary(&bndl, raw.len()));
+        count += 1;
+    });
+}
+
+/// Concatenates the bundles of all inputs into a single stream on stdout.
+fn cat(inputs: &[&String], filter: &StreamFilter, hex: bool) {
+    let mut stdout = std::io::stdout();
+    for input in inputs {
+        let buf = read_bundle_input(input).unwrap_or_else(|err| exit_with(err));
+        for_each_bundle(&buf, filter, |_, raw| {
+            if hex {
+                println!("{}", hexify(raw));
+            } else {
+                stdout.write_all(raw).unwrap();
+            }
+        });
+    }
+}
+
+fn buf_to_bundle(buf: Vec<u8>, payload_only: bool, format: DecodeFormat) {
+    let mut bndl = Bundle::try_from(buf.as_slice())
+        .unwrap_or_else(|err| exit_with(format!("error decoding bundle: {}", err)));
     if payload_only {
         if bndl.payload().is_some() {
             std::io::stdout()
@@ -116,7 +1067,26 @@ fn buf_to_bundle(buf: Vec<u8>, payload_only: bool) {
                 .unwrap();
         }
     } else {
-        println!("{:#?}", &bndl);
+        match format {
+            DecodeFormat::Debug => {
+                println!("{:#?}", &bndl);
+                if let Some(record) = admin_record_of(&bndl) {
+                    println!("{:#?}", record);
+                }
+            }
+            DecodeFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&bundle_to_json(&bndl)).unwrap()
+            ),
+            DecodeFormat::Diag => match cbor_diag(&buf) {
+                Some(diag) => println!("{}", diag),
+                None => exit_with(format!(
+                    "error rendering diagnostic notation, CBOR nested deeper than {} levels",
+                    MAX_DIAG_DEPTH
+                )),
+            },
+            DecodeFormat::Summary => println!("{}", bundle_summary(&bndl)),
+        }
     }
     if let Err(errs) = bndl.validate() {
         eprintln!("Error validating bundle: {:?}", errs);
@@ -153,33 +1123,141 @@ fn main() {
             }
         }
         "encode" => {
-            let hex = if args.len() == 4 {
-                false
-            } else if args.len() == 5 {
-                args[4] == "-x"
-            } else {
+            if args.len() < 4 {
                 usage(&args[0]);
                 std::process::exit(1);
-            };
-            if args[3] == "-" {
-                encode_from_stdin(&args[2], hex);
+            }
+            let mut manifest = Manifest::from_file(&args[2]).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            });
+            let mut hex = false;
+            let mut opts = args[4..].iter();
+            while let Some(opt) = opts.next() {
+                let result = match opt.as_str() {
+                    "-x" => {
+                        hex = true;
+                        Ok(())
+                    }
+                    "-s" | "--set" => opts
+                        .next()
+                        .ok_or_else(|| format!("{} requires key=value", opt))
+                        .and_then(|kv| manifest.set_line(kv)),
+                    _ => {
+                        usage(&args[0]);
+                        std::process::exit(1);
+                    }
+                };
+                if let Err(err) = result {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+            let payload = if args[3] == "-" {
+                let mut payload: Vec<u8> = Vec::new();
+                io::stdin()
+                    .read_to_end(&mut payload)
+                    .expect("Error reading from stdin.");
+                payload
             } else {
-                encode(&args[2], &args[3], hex);
+                fs::read(&args[3]).expect("error reading bundle payload")
+            };
+            encode(&manifest, payload, hex);
+        }
+        "update" | "set-crc" | "strip" | "sign" | "fragment" | "reassemble" => {
+            if let Err(err) = transform(cmd, &args[2..]) {
+                exit_with(err);
             }
         }
-        "decode" => {
-            let payload_only = if args.len() == 3 {
-                false
-            } else if args.len() == 4 {
-                args[3] == "-p"
-            } else {
+        "status-report" => {
+            if let Err(err) = status_report(&args[2..]) {
+                exit_with(err);
+            }
+        }
+        "validate" | "lint" => {
+            if args.len() < 3 {
                 usage(&args[0]);
                 std::process::exit(1);
+            }
+            let key = match &args[3..] {
+                [] => None,
+                [opt, keyfile] if opt == "-k" || opt == "--key" => {
+                    Some(read_key(keyfile).unwrap_or_else(|err| {
+                        eprintln!("{}", err);
+                        std::process::exit(1);
+                    }))
+                }
+                _ => {
+                    usage(&args[0]);
+                    std::process::exit(1);
+                }
             };
-            if args[2] == "-" {
-                decode_from_stdin(payload_only);
+            std::process::exit(check_bundle(&args[2], key, cmd == "lint"));
+        }
+        "decode" => {
+            if args.len() < 3 {
+                usage(&args[0]);
+                std::process::exit(1);
+            }
+            let mut payload_only = false;
+            let mut stream = false;
+            let mut filter = StreamFilter::default();
+            let mut format = DecodeFormat::Debug;
+            let mut opts = args[3..].iter();
+            while let Some(opt) = opts.next() {
+                match opt.as_str() {
+                    "-p" => payload_only = true,
+                    "--stream" => stream = true,
+                    "--format" | "-f" => {
+                        format = opts
+                            .next()
+                            .map(|f| f.parse())
+                            .unwrap_or_else(|| Err("missing output format".to_string()))
+                            .unwrap_or_else(|err| {
+                                eprintln!("{}", err);
+                                std::process::exit(1);
+                            });
+                    }
+                    opt if filter.parse_opt(opt, &mut opts) => {}
+                    _ => {
+                        usage(&args[0]);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            if stream {
+                let buf = if args[2] == "-" {
+                    read_bundle_input("-")
+                } else {
+                    unhexify(&args[2]).map_err(|e| format!("invalid hex input: {}", e))
+                }
+                .unwrap_or_else(|err| exit_with(err));
+                decode_stream(&buf, format, &filter);
+            } else if args[2] == "-" {
+                decode_from_stdin(payload_only, format);
             } else {
-                decode(&args[2], payload_only);
+                decode(&args[2], payload_only, format);
+            }
+        }
+        "split" | "cat" => {
+            let mut filter = StreamFilter::default();
+            let mut hex = false;
+            let mut positional = Vec::new();
+            let mut opts = args[2..].iter();
+            while let Some(opt) = opts.next() {
+                match opt.as_str() {
+                    "-x" => hex = true,
+                    o if filter.parse_opt(o, &mut opts) => {}
+                    _ => positional.push(opt),
+                }
+            }
+            match (cmd.as_str(), &positional[..]) {
+                ("split", [input, prefix]) => split(input, prefix, &filter),
+                ("cat", inputs) if !inputs.is_empty() => cat(inputs, &filter, hex),
+                _ => {
+                    usage(&args[0]);
+                    std::process::exit(1);
+                }
             }
         }
         "dtntime" => {
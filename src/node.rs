@@ -0,0 +1,129 @@
+use crate::administrative_record::{
+    StatusInformationPos, StatusReportError, StatusReportReason,
+    build_status_report_bundle_with_defaults, new_multi_status_report, requested_status_items,
+};
+use crate::bundle::{Bundle, ByteBuffer};
+use crate::canonical::{HopCountBlock, PREVIOUS_NODE_BLOCK, PreviousNodeBlock, new_payload_block};
+use crate::crc::{CRC_NO, CrcRawType};
+use crate::dtntime::{CreationTimestamp, SequenceCounter};
+use crate::eid::EndpointID;
+use crate::flags::BlockControlFlags;
+use crate::primary::{DEFAULT_LIFETIME, PrimaryBlockBuilder, PrimaryBuilderError, PrimaryDefaults};
+use core::time::Duration;
+
+/// Settings shared by all bundles created or forwarded by one local node.
+///
+/// Each context owns its creation timestamp sequence, so several nodes in one process
+/// never hand out the same timestamp for their own source.
+#[derive(Debug)]
+pub struct NodeContext {
+    node_id: EndpointID,
+    crc_type: CrcRawType,
+    lifetime: Duration,
+    hop_limit: Option<u8>,
+    sequence: SequenceCounter,
+}
+
+impl NodeContext {
+    /// Create a context for `node_id` without CRCs, with [`DEFAULT_LIFETIME`] and no hop limit.
+    pub fn new(node_id: EndpointID) -> NodeContext {
+        NodeContext {
+            node_id,
+            crc_type: CRC_NO,
+            lifetime: DEFAULT_LIFETIME,
+            hop_limit: None,
+            sequence: SequenceCounter::new(),
+        }
+    }
+    pub fn crc_type(mut self, crc_type: CrcRawType) -> Self {
+        self.crc_type = crc_type;
+        self
+    }
+    pub fn lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+    /// Add a hop count block with this limit to new bundles, zero disables it.
+    pub fn hop_limit(mut self, limit: u8) -> Self {
+        self.hop_limit = (limit != 0).then_some(limit);
+        self
+    }
+
+    pub fn node_id(&self) -> &EndpointID {
+        &self.node_id
+    }
+
+    /// Next creation timestamp from this node's own sequence counter.
+    pub fn next_timestamp(&self) -> CreationTimestamp {
+        self.sequence.next()
+    }
+
+    /// Create a bundle from this node to `dst` carrying `payload`.
+    ///
+    /// Bundles with other primary block settings can be built by passing the context to
+    /// [`PrimaryBlockBuilder::build_with`].
+    pub fn new_bundle(
+        &self,
+        dst: EndpointID,
+        payload: ByteBuffer,
+    ) -> Result<Bundle, PrimaryBuilderError> {
+        let pblock = PrimaryBlockBuilder::new()
+            .destination(dst)
+            .build_with(self)?;
+        let mut bndl = Bundle::new(pblock, Vec::new());
+        bndl.add_canonical_block(new_payload_block(BlockControlFlags::empty(), payload));
+        if let Some(limit) = self.hop_limit {
+            let hcblock = HopCountBlock::new(limit).expect("hop limit is never zero");
+            bndl.add_canonical_block(hcblock.into());
+        }
+        bndl.set_crc(self.crc_type);
+        Ok(bndl)
+    }
+
+    /// Create a status report from this node about `bundle`, see
+    /// [`status_report_bundle`](crate::administrative_record::status_report_bundle).
+    ///
+    /// The report uses the lifetime and creation timestamp sequence of this node.
+    pub fn status_report_for(
+        &self,
+        bundle: &Bundle,
+        status_items: &[StatusInformationPos],
+        reason: StatusReportReason,
+    ) -> Result<Bundle, StatusReportError> {
+        let requested = requested_status_items(bundle, status_items)?;
+        build_status_report_bundle_with_defaults(
+            bundle,
+            self,
+            self.crc_type,
+            new_multi_status_report(bundle, &requested, reason),
+        )
+    }
+
+    /// Prepare a received bundle for forwarding by this node.
+    ///
+    /// Adds a previous node block if none is present yet, then applies
+    /// [`Bundle::update_extensions`] with `residence_time` spent at this node.
+    /// Returns false if the bundle must not be forwarded anymore.
+    pub fn forward_update(&self, bundle: &mut Bundle, residence_time: Duration) -> bool {
+        if bundle
+            .extension_block_by_type(PREVIOUS_NODE_BLOCK)
+            .is_none()
+        {
+            let pnblock = PreviousNodeBlock::new(self.node_id.clone()).crc(self.crc_type);
+            bundle.add_canonical_block(pnblock.into());
+        }
+        bundle.update_extensions(self.node_id.clone(), residence_time)
+    }
+}
+
+impl PrimaryDefaults for NodeContext {
+    fn source(&self) -> EndpointID {
+        self.node_id.clone()
+    }
+    fn creation_timestamp(&self) -> CreationTimestamp {
+        self.next_timestamp()
+    }
+    fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+}
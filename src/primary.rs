@@ -1,5 +1,6 @@
 use crate::error::Error;
 use crate::error::ErrorList;
+use crate::helpers::{cbor_head_len, cbor_string_len};
 
 use super::bundle::*;
 use super::crc::*;
@@ -19,29 +20,53 @@ use thiserror::Error;
  *
  ******************************/
 
+/// Lifetime used by [`PrimaryBlockBuilder`] if none is set explicitly.
+pub const DEFAULT_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
 #[derive(Error, Debug)]
 pub enum PrimaryBuilderError {
     #[error("no destination endpoint was provided")]
     NoDestination,
+    #[error("invalid primary block: {0:?}")]
+    Invalid(ErrorList),
+}
+
+/// Source of the values [`PrimaryBlockBuilder::build_with`] uses for fields that were not set.
+pub trait PrimaryDefaults {
+    /// Source endpoint, also used as report-to endpoint if none is set.
+    fn source(&self) -> EndpointID {
+        EndpointID::none()
+    }
+    /// Creation timestamp for a new bundle, called once per built block.
+    fn creation_timestamp(&self) -> CreationTimestamp {
+        CreationTimestamp::now()
+    }
+    fn lifetime(&self) -> Duration {
+        DEFAULT_LIFETIME
+    }
 }
 
+/// Defaults used by [`PrimaryBlockBuilder::build`]: anonymous source, the system clock and
+/// [`DEFAULT_LIFETIME`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemDefaults;
+
+impl PrimaryDefaults for SystemDefaults {}
+
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct PrimaryBlockBuilder {
     bundle_control_flags: BundleControlFlagsType,
     crc: CrcValue,
     destination: EndpointID,
-    source: EndpointID,
-    report_to: EndpointID,
-    creation_timestamp: CreationTimestamp,
-    lifetime: Duration,
+    source: Option<EndpointID>,
+    report_to: Option<EndpointID>,
+    creation_timestamp: Option<CreationTimestamp>,
+    lifetime: Option<Duration>,
     fragmentation_offset: FragOffsetType,
     total_data_length: TotalDataLengthType,
 }
 impl PrimaryBlockBuilder {
     pub fn new() -> Self {
-        // let mut builder = PrimaryBlockBuilder::default();
-        // should this be set by default?
-        // builder.creation_timestamp = CreationTimestamp::now();
         PrimaryBlockBuilder::default()
     }
     pub fn bundle_control_flags(mut self, flags: BundleControlFlagsType) -> Self {
@@ -57,19 +82,19 @@ impl PrimaryBlockBuilder {
         self
     }
     pub fn source(mut self, source: EndpointID) -> Self {
-        self.source = source;
+        self.source = Some(source);
         self
     }
     pub fn report_to(mut self, report_to: EndpointID) -> Self {
-        self.report_to = report_to;
+        self.report_to = Some(report_to);
         self
     }
     pub fn creation_timestamp(mut self, creation_timestamp: CreationTimestamp) -> Self {
-        self.creation_timestamp = creation_timestamp;
+        self.creation_timestamp = Some(creation_timestamp);
         self
     }
     pub fn lifetime(mut self, lifetime: Duration) -> Self {
-        self.lifetime = lifetime;
+        self.lifetime = Some(lifetime);
         self
     }
     pub fn fragmentation_offset(mut self, offset: FragOffsetType) -> Self {
@@ -80,28 +105,52 @@ impl PrimaryBlockBuilder {
         self.total_data_length = length;
         self
     }
+    /// Build the primary block, unset fields are taken from [`SystemDefaults`].
     pub fn build(self) -> Result<PrimaryBlock, PrimaryBuilderError> {
+        self.build_with(&SystemDefaults)
+    }
+    /// Build the primary block, unset fields are taken from `defaults`.
+    ///
+    /// All problems found by [`PrimaryBlock::validate`], apart from the not yet calculated
+    /// CRC, as well as a zero lifetime are returned together.
+    pub fn build_with(
+        self,
+        defaults: &impl PrimaryDefaults,
+    ) -> Result<PrimaryBlock, PrimaryBuilderError> {
         if self.destination == EndpointID::none() {
-            Err(PrimaryBuilderError::NoDestination)
+            return Err(PrimaryBuilderError::NoDestination);
+        }
+        let source = self.source.unwrap_or_else(|| defaults.source());
+        let pblock = PrimaryBlock {
+            version: DTN_VERSION,
+            bundle_control_flags: self.bundle_control_flags,
+            crc: self.crc,
+            destination: self.destination,
+            report_to: self.report_to.unwrap_or_else(|| source.clone()),
+            source,
+            creation_timestamp: self
+                .creation_timestamp
+                .unwrap_or_else(|| defaults.creation_timestamp()),
+            lifetime: self.lifetime.unwrap_or_else(|| defaults.lifetime()),
+            fragmentation_offset: self.fragmentation_offset,
+            total_data_length: self.total_data_length,
+            received: ReceivedEncoding::default(),
+        };
+
+        let mut errors = pblock.field_errors();
+        if pblock.lifetime.is_zero() {
+            errors.push(Error::PrimaryBlockError("Lifetime is zero".to_string()));
+        }
+        if errors.is_empty() {
+            Ok(pblock)
         } else {
-            Ok(PrimaryBlock {
-                version: DTN_VERSION,
-                bundle_control_flags: self.bundle_control_flags,
-                crc: self.crc,
-                destination: self.destination,
-                source: self.source,
-                report_to: self.report_to,
-                creation_timestamp: self.creation_timestamp,
-                lifetime: self.lifetime,
-                fragmentation_offset: self.fragmentation_offset,
-                total_data_length: self.total_data_length,
-            })
+            Err(PrimaryBuilderError::Invalid(errors))
         }
     }
 }
 
 //#[derive(Debug, Serialize_tuple, Deserialize_tuple, Clone)]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct PrimaryBlock {
     version: DtnVersionType,
     pub bundle_control_flags: BundleControlFlagsType,
@@ -114,6 +163,24 @@ pub struct PrimaryBlock {
     pub lifetime: Duration,
     pub fragmentation_offset: FragOffsetType,
     pub total_data_length: TotalDataLengthType,
+    pub(crate) received: ReceivedEncoding<Box<PrimaryBlock>>,
+}
+
+impl fmt::Debug for PrimaryBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PrimaryBlock")
+            .field("version", &self.version)
+            .field("bundle_control_flags", &self.bundle_control_flags)
+            .field("crc", &self.crc)
+            .field("destination", &self.destination)
+            .field("source", &self.source)
+            .field("report_to", &self.report_to)
+            .field("creation_timestamp", &self.creation_timestamp)
+            .field("lifetime", &self.lifetime)
+            .field("fragmentation_offset", &self.fragmentation_offset)
+            .field("total_data_length", &self.total_data_length)
+            .finish()
+    }
 }
 
 impl Serialize for PrimaryBlock {
@@ -247,6 +314,7 @@ impl<'de> Deserialize<'de> for PrimaryBlock {
                     lifetime,
                     fragmentation_offset,
                     total_data_length,
+                    received: ReceivedEncoding::default(),
                 })
             }
         }
@@ -261,6 +329,10 @@ impl Default for PrimaryBlock {
 }
 
 impl PrimaryBlock {
+    /// The received bytes of the block, as long as it has not been changed since decoding.
+    pub(crate) fn received_raw(&self) -> Option<&[u8]> {
+        self.received.unchanged(self)
+    }
     pub fn new() -> PrimaryBlock {
         PrimaryBlock {
             version: DTN_VERSION,
@@ -273,6 +345,7 @@ impl PrimaryBlock {
             lifetime: Duration::new(0, 0),
             fragmentation_offset: 0,
             total_data_length: 0,
+            received: ReceivedEncoding::default(),
         }
     }
 
@@ -280,6 +353,24 @@ impl PrimaryBlock {
         self.bundle_control_flags
             .contains(BundleControlFlags::BUNDLE_IS_FRAGMENT)
     }
+    /// Length of the CBOR encoding of the block, computed without encoding it.
+    ///
+    /// CRC values take up their full size even if not calculated yet.
+    pub fn encoded_len(&self) -> usize {
+        let mut len = 1 // array head, at most 11 elements
+            + cbor_head_len(u64::from(self.version))
+            + cbor_head_len(self.bundle_control_flags)
+            + cbor_head_len(self.crc.to_code().into())
+            + self.destination.encoded_len()
+            + self.source.encoded_len()
+            + self.report_to.encoded_len()
+            + self.creation_timestamp.encoded_len()
+            + cbor_head_len(self.lifetime.as_millis() as u64);
+        if self.has_fragmentation() {
+            len += cbor_head_len(self.fragmentation_offset) + cbor_head_len(self.total_data_length);
+        }
+        len + self.crc.bytes().map_or(0, |crc| cbor_string_len(crc.len()))
+    }
     pub fn is_lifetime_exceeded(&self) -> bool {
         if self.creation_timestamp.dtntime() == 0 {
             return false;
@@ -289,6 +380,25 @@ impl PrimaryBlock {
         self.creation_timestamp.dtntime() + (self.lifetime.as_millis() as u64) <= now
     }
     pub fn validate(&self) -> Result<(), ErrorList> {
+        let mut errors = self.field_errors();
+
+        match crc_status(self) {
+            CrcStatus::Absent | CrcStatus::Valid => {}
+            CrcStatus::Invalid => {
+                errors.push(Error::PrimaryBlockError("CRC check failed".to_string()))
+            }
+            CrcStatus::Unsupported(code) => errors.push(Error::PrimaryBlockError(format!(
+                "unsupported CRC type {}",
+                code
+            ))),
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(())
+    }
+    fn field_errors(&self) -> ErrorList {
         let mut errors: ErrorList = Vec::new();
 
         if self.version != DTN_VERSION {
@@ -314,14 +424,14 @@ impl PrimaryBlock {
             errors.push(chk_err.into());
         }
 
-        if self.has_crc() && !self.clone().check_crc() {
-            errors.push(Error::PrimaryBlockError("CRC check failed".to_string()));
+        if !self.has_fragmentation()
+            && (self.fragmentation_offset != 0 || self.total_data_length != 0)
+        {
+            errors.push(Error::PrimaryBlockError(
+                "Fragmentation fields set without fragment flag".to_string(),
+            ));
         }
-
-        if !errors.is_empty() {
-            return Err(errors);
-        }
-        Ok(())
+        errors
     }
 }
 
@@ -329,8 +439,12 @@ impl CrcBlock for PrimaryBlock {
     fn crc_value(&self) -> &CrcValue {
         &self.crc
     }
+    fn received_crc(&self) -> Option<&CrcValue> {
+        self.received.current_crc(self)
+    }
     fn set_crc(&mut self, crc: CrcValue) {
         self.crc = crc;
+        self.received.invalidate();
     }
 }
 impl Block for PrimaryBlock {
@@ -358,5 +472,6 @@ pub fn new_primary_block(
         lifetime,
         fragmentation_offset: 0,
         total_data_length: 0,
+        received: ReceivedEncoding::default(),
     }
 }
@@ -0,0 +1,666 @@
+//! Python bindings, enabled with the `python` feature.
+//!
+//! Build the extension module with `maturin develop --features python` or see the README.
+
+use core::convert::TryFrom;
+use core::time::Duration;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+
+use crate::administrative_record::*;
+use crate::bundle::Bundle;
+use crate::canonical::*;
+use crate::crc::{self, CrcBlock, CrcRawType};
+use crate::dtntime::CreationTimestamp;
+use crate::eid::EndpointID;
+use crate::flags::BlockControlFlags;
+use crate::primary::PrimaryBlockBuilder;
+
+create_exception!(
+    bp7,
+    Bp7Error,
+    PyValueError,
+    "Error raised by bp7 operations."
+);
+
+fn bp7_err(e: impl ToString) -> PyErr {
+    Bp7Error::new_err(e.to_string())
+}
+
+/// Accepts either an `EndpointID` or its string representation.
+fn eid_arg(obj: &Bound<'_, PyAny>) -> PyResult<EndpointID> {
+    if let Ok(eid) = obj.cast::<PyEndpointID>() {
+        return Ok(eid.borrow().inner.clone());
+    }
+    let s: String = obj.extract()?;
+    EndpointID::try_from(s.as_str()).map_err(bp7_err)
+}
+
+fn crc_arg(crc_type: CrcRawType) -> PyResult<CrcRawType> {
+    crc::validate_crc_type(crc_type).map_err(bp7_err)
+}
+
+/// Accepts a status information position or its name, e.g., `delivered`.
+fn status_arg(obj: &Bound<'_, PyAny>) -> PyResult<StatusInformationPos> {
+    let status = match obj.extract::<StatusInformationPos>() {
+        Ok(pos) => pos,
+        Err(_) => {
+            let name: String = obj.extract()?;
+            status_information_from_name(&name)
+                .ok_or_else(|| bp7_err(format!("unknown status information `{}`", name)))?
+        }
+    };
+    if status >= MAX_STATUS_INFORMATION_POS {
+        return Err(bp7_err(format!("unknown status information {}", status)));
+    }
+    Ok(status)
+}
+
+fn block_data_to_py<'py>(py: Python<'py>, data: &CanonicalData) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match data {
+        CanonicalData::HopCount(limit, count) => {
+            let d = PyDict::new(py);
+            d.set_item("limit", limit)?;
+            d.set_item("count", count)?;
+            d.into_any()
+        }
+        CanonicalData::Data(buf) | CanonicalData::Unknown(buf) => PyBytes::new(py, buf).into_any(),
+        CanonicalData::BundleAge(age) => age.into_pyobject(py)?.into_any(),
+        CanonicalData::PreviousNode(eid) => eid.to_string().into_pyobject(py)?.into_any(),
+        CanonicalData::DecodingError => py.None().into_bound(py),
+    })
+}
+
+fn status_report_to_py<'py>(py: Python<'py>, sr: &StatusReport) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    let asserted = PyList::empty(py);
+    let times = PyDict::new(py);
+    for (pos, item) in sr.status_information.iter().enumerate() {
+        let Some(name) = status_information_name(pos as StatusInformationPos) else {
+            continue;
+        };
+        if item.asserted {
+            asserted.append(name)?;
+            if item.status_requested {
+                times.set_item(name, item.time)?;
+            }
+        }
+    }
+    d.set_item("asserted", asserted)?;
+    d.set_item("times", times)?;
+    d.set_item("reason", sr.report_reason)?;
+    d.set_item("reason_name", status_report_reason_name(sr.report_reason))?;
+    d.set_item("refbundle", sr.refbundle())?;
+    d.set_item("source", sr.source_node.to_string())?;
+    d.set_item(
+        "creation_timestamp",
+        (sr.timestamp.dtntime(), sr.timestamp.seqno()),
+    )?;
+    d.set_item("frag_offset", sr.frag_offset)?;
+    d.set_item("frag_len", sr.frag_len)?;
+    Ok(d)
+}
+
+/// A bundle protocol endpoint ID, e.g., `dtn://node1/inbox` or `ipn:23.42`.
+#[pyclass(
+    name = "EndpointID",
+    module = "bp7",
+    eq,
+    frozen,
+    hash,
+    skip_from_py_object
+)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PyEndpointID {
+    pub inner: EndpointID,
+}
+
+#[pymethods]
+impl PyEndpointID {
+    #[new]
+    fn new(eid: &str) -> PyResult<Self> {
+        Ok(PyEndpointID {
+            inner: EndpointID::try_from(eid).map_err(bp7_err)?,
+        })
+    }
+
+    /// The null endpoint `dtn:none`.
+    #[staticmethod]
+    fn none() -> Self {
+        PyEndpointID {
+            inner: EndpointID::none(),
+        }
+    }
+
+    #[getter]
+    fn scheme(&self) -> String {
+        self.inner.scheme()
+    }
+
+    #[getter]
+    fn node(&self) -> Option<String> {
+        self.inner.node()
+    }
+
+    #[getter]
+    fn node_id(&self) -> Option<String> {
+        self.inner.node_id()
+    }
+
+    #[getter]
+    fn service_name(&self) -> Option<String> {
+        self.inner.service_name()
+    }
+
+    fn is_node_id(&self) -> bool {
+        self.inner.is_node_id()
+    }
+
+    fn is_non_singleton(&self) -> bool {
+        self.inner.is_non_singleton()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("EndpointID('{}')", self.inner)
+    }
+}
+
+/// A canonical bundle block, e.g., the payload or an extension block.
+#[pyclass(name = "CanonicalBlock", module = "bp7", eq, skip_from_py_object)]
+#[derive(Clone, PartialEq)]
+pub struct PyCanonicalBlock {
+    pub inner: CanonicalBlock,
+}
+
+#[pymethods]
+impl PyCanonicalBlock {
+    /// Creates a block with raw block-type-specific data.
+    #[new]
+    #[pyo3(signature = (block_type, block_number, data, flags = 0))]
+    fn new(block_type: CanonicalBlockType, block_number: u64, data: Vec<u8>, flags: u8) -> Self {
+        let data = if block_type == PAYLOAD_BLOCK {
+            CanonicalData::Data(data)
+        } else {
+            CanonicalData::Unknown(data)
+        };
+        PyCanonicalBlock {
+            inner: new_canonical_block(block_type, block_number, flags, data),
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (data, flags = 0))]
+    fn payload(data: Vec<u8>, flags: u8) -> Self {
+        PyCanonicalBlock {
+            inner: new_payload_block(BlockControlFlags::from_bits_truncate(flags), data),
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (block_number, limit, flags = 0))]
+    fn hop_count(block_number: u64, limit: u8, flags: u8) -> PyResult<Self> {
+        if limit == 0 {
+            return Err(bp7_err("hop limit must be between 1 and 255"));
+        }
+        Ok(PyCanonicalBlock {
+            inner: new_hop_count_block(
+                block_number,
+                BlockControlFlags::from_bits_truncate(flags),
+                limit,
+            ),
+        })
+    }
+
+    /// Creates a bundle age block, `age` is given in milliseconds.
+    #[staticmethod]
+    #[pyo3(signature = (block_number, age, flags = 0))]
+    fn bundle_age(block_number: u64, age: u64, flags: u8) -> Self {
+        PyCanonicalBlock {
+            inner: new_bundle_age_block(
+                block_number,
+                BlockControlFlags::from_bits_truncate(flags),
+                age,
+            ),
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (block_number, node, flags = 0))]
+    fn previous_node(block_number: u64, node: &Bound<'_, PyAny>, flags: u8) -> PyResult<Self> {
+        Ok(PyCanonicalBlock {
+            inner: new_previous_node_block(
+                block_number,
+                BlockControlFlags::from_bits_truncate(flags),
+                eid_arg(node)?,
+            ),
+        })
+    }
+
+    #[getter]
+    fn block_type(&self) -> CanonicalBlockType {
+        self.inner.block_type
+    }
+
+    #[getter]
+    fn block_number(&self) -> u64 {
+        self.inner.block_number
+    }
+
+    #[getter]
+    fn flags(&self) -> u8 {
+        self.inner.block_control_flags
+    }
+
+    #[getter]
+    fn crc_type(&self) -> CrcRawType {
+        self.inner.crc_type()
+    }
+
+    /// The decoded block data: bytes, an int, a str or a dict depending on the block type.
+    #[getter]
+    fn data<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        block_data_to_py(py, self.inner.data())
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let d = PyDict::new(py);
+        d.set_item("block_type", self.inner.block_type)?;
+        d.set_item("block_type_name", block_type_name(self.inner.block_type))?;
+        d.set_item("block_number", self.inner.block_number)?;
+        d.set_item("flags", self.inner.block_control_flags)?;
+        d.set_item("crc_type", self.inner.crc_type())?;
+        d.set_item("data", self.data(py)?)?;
+        Ok(d)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CanonicalBlock(block_type={}, block_number={}, flags={:#x})",
+            self.inner.block_type, self.inner.block_number, self.inner.block_control_flags
+        )
+    }
+}
+
+/// Builder for the primary block of a new bundle.
+///
+/// The creation timestamp defaults to the time of building and the lifetime to one hour.
+#[pyclass(name = "PrimaryBlockBuilder", module = "bp7")]
+pub struct PyPrimaryBlockBuilder {
+    pub inner: PrimaryBlockBuilder,
+}
+
+impl PyPrimaryBlockBuilder {
+    fn update(
+        mut slf: PyRefMut<'_, Self>,
+        f: impl FnOnce(PrimaryBlockBuilder) -> PrimaryBlockBuilder,
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = f(std::mem::take(&mut slf.inner));
+        slf
+    }
+}
+
+#[pymethods]
+impl PyPrimaryBlockBuilder {
+    #[new]
+    fn new() -> Self {
+        PyPrimaryBlockBuilder {
+            inner: PrimaryBlockBuilder::default(),
+        }
+    }
+
+    fn destination<'py>(
+        slf: PyRefMut<'py, Self>,
+        eid: &Bound<'_, PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let eid = eid_arg(eid)?;
+        Ok(Self::update(slf, |b| b.destination(eid)))
+    }
+
+    fn source<'py>(
+        slf: PyRefMut<'py, Self>,
+        eid: &Bound<'_, PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let eid = eid_arg(eid)?;
+        Ok(Self::update(slf, |b| b.source(eid)))
+    }
+
+    fn report_to<'py>(
+        slf: PyRefMut<'py, Self>,
+        eid: &Bound<'_, PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let eid = eid_arg(eid)?;
+        Ok(Self::update(slf, |b| b.report_to(eid)))
+    }
+
+    fn flags(slf: PyRefMut<'_, Self>, flags: u64) -> PyRefMut<'_, Self> {
+        Self::update(slf, |b| b.bundle_control_flags(flags))
+    }
+
+    /// Sets the creation timestamp, `time` is given in milliseconds since the DTN epoch.
+    #[pyo3(signature = (time, seqno = 0))]
+    fn creation_timestamp(slf: PyRefMut<'_, Self>, time: u64, seqno: u64) -> PyRefMut<'_, Self> {
+        Self::update(slf, |b| {
+            b.creation_timestamp(CreationTimestamp::with_time_and_seq(time, seqno))
+        })
+    }
+
+    /// Sets the lifetime in milliseconds.
+    fn lifetime(slf: PyRefMut<'_, Self>, lifetime: u64) -> PyRefMut<'_, Self> {
+        Self::update(slf, |b| b.lifetime(Duration::from_millis(lifetime)))
+    }
+}
+
+/// A bundle, consisting of a primary block and canonical blocks.
+#[pyclass(name = "Bundle", module = "bp7", eq, skip_from_py_object)]
+#[derive(Clone, PartialEq)]
+pub struct PyBundle {
+    pub inner: Bundle,
+}
+
+#[pymethods]
+impl PyBundle {
+    /// Builds a bundle from a primary block builder and a list of blocks.
+    #[new]
+    #[pyo3(signature = (primary, blocks, crc_type = crc::CRC_NO))]
+    fn new(
+        primary: PyRef<'_, PyPrimaryBlockBuilder>,
+        blocks: Vec<PyRef<'_, PyCanonicalBlock>>,
+        crc_type: CrcRawType,
+    ) -> PyResult<Self> {
+        let primary = primary.inner.clone().build().map_err(bp7_err)?;
+        let mut bndl = Bundle::new(primary, blocks.iter().map(|b| b.inner.clone()).collect());
+        bndl.set_crc(crc_arg(crc_type)?);
+        bndl.calculate_crc();
+        Ok(PyBundle { inner: bndl })
+    }
+
+    /// Decodes a bundle from CBOR without validating it, see `validate`.
+    #[staticmethod]
+    fn from_cbor(data: &[u8]) -> PyResult<Self> {
+        Ok(PyBundle {
+            inner: Bundle::try_from(data).map_err(bp7_err)?,
+        })
+    }
+
+    fn to_cbor<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.inner.clone().to_cbor())
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let pb = &self.inner.primary;
+        let primary = PyDict::new(py);
+        primary.set_item("version", crate::bundle::DTN_VERSION)?;
+        primary.set_item("flags", pb.bundle_control_flags)?;
+        primary.set_item("crc_type", pb.crc_type())?;
+        primary.set_item("destination", pb.destination.to_string())?;
+        primary.set_item("source", pb.source.to_string())?;
+        primary.set_item("report_to", pb.report_to.to_string())?;
+        primary.set_item(
+            "creation_timestamp",
+            (
+                pb.creation_timestamp.dtntime(),
+                pb.creation_timestamp.seqno(),
+            ),
+        )?;
+        primary.set_item("lifetime", pb.lifetime.as_millis() as u64)?;
+        if pb.has_fragmentation() {
+            primary.set_item("fragmentation_offset", pb.fragmentation_offset)?;
+            primary.set_item("total_data_length", pb.total_data_length)?;
+        }
+
+        let blocks = PyList::empty(py);
+        for b in self.inner.canonicals.iter() {
+            let b = PyCanonicalBlock { inner: b.clone() };
+            blocks.append(b.to_dict(py)?)?;
+        }
+
+        let d = PyDict::new(py);
+        d.set_item("id", self.inner.id())?;
+        d.set_item("primary", primary)?;
+        d.set_item("blocks", blocks)?;
+        if let Some(sr) = self.status_report(py)? {
+            d.set_item("status_report", sr)?;
+        }
+        Ok(d)
+    }
+
+    #[getter]
+    fn id(&self) -> String {
+        self.inner.id()
+    }
+
+    #[getter]
+    fn source(&self) -> PyEndpointID {
+        PyEndpointID {
+            inner: self.inner.primary.source.clone(),
+        }
+    }
+
+    #[getter]
+    fn destination(&self) -> PyEndpointID {
+        PyEndpointID {
+            inner: self.inner.primary.destination.clone(),
+        }
+    }
+
+    #[getter]
+    fn report_to(&self) -> PyEndpointID {
+        PyEndpointID {
+            inner: self.inner.primary.report_to.clone(),
+        }
+    }
+
+    #[getter]
+    fn flags(&self) -> u64 {
+        self.inner.primary.bundle_control_flags
+    }
+
+    #[getter]
+    fn creation_timestamp(&self) -> (u64, u64) {
+        let ts = &self.inner.primary.creation_timestamp;
+        (ts.dtntime(), ts.seqno())
+    }
+
+    /// The lifetime in milliseconds.
+    #[getter]
+    fn lifetime(&self) -> u64 {
+        self.inner.primary.lifetime.as_millis() as u64
+    }
+
+    #[getter]
+    fn crc_type(&self) -> CrcRawType {
+        self.inner.primary.crc_type()
+    }
+
+    #[getter]
+    fn blocks(&self) -> Vec<PyCanonicalBlock> {
+        self.inner
+            .canonicals
+            .iter()
+            .map(|b| PyCanonicalBlock { inner: b.clone() })
+            .collect()
+    }
+
+    /// Returns the first block of the given type, if any.
+    fn block(&self, block_type: CanonicalBlockType) -> Option<PyCanonicalBlock> {
+        self.inner
+            .extension_block_by_type(block_type)
+            .map(|b| PyCanonicalBlock { inner: b.clone() })
+    }
+
+    #[getter]
+    fn payload<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+        self.inner.payload().map(|p| PyBytes::new(py, p))
+    }
+
+    fn set_payload(&mut self, data: Vec<u8>) {
+        self.inner.set_payload(data);
+        self.inner.calculate_crc();
+    }
+
+    /// Adds a block with an automatically assigned block number.
+    ///
+    /// Additional payload, hop count, bundle age or previous node blocks are ignored.
+    fn add_block(&mut self, block: PyRef<'_, PyCanonicalBlock>) {
+        let _ = self.inner.add_block(block.inner.clone());
+    }
+
+    fn remove_block(&mut self, block_number: u64) -> Option<PyCanonicalBlock> {
+        let block = self.inner.remove_block(block_number)?;
+        self.inner.calculate_crc();
+        Some(PyCanonicalBlock { inner: block })
+    }
+
+    fn set_crc(&mut self, crc_type: CrcRawType) -> PyResult<()> {
+        self.inner.set_crc(crc_arg(crc_type)?);
+        self.inner.calculate_crc();
+        Ok(())
+    }
+
+    /// Raises `Bp7Error` listing all validation errors if the bundle is invalid.
+    fn validate(&self) -> PyResult<()> {
+        self.inner.validate().map_err(|errs| {
+            let msgs: Vec<String> = errs.iter().map(|e| e.to_string()).collect();
+            bp7_err(msgs.join("\n"))
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.inner.validate().is_ok()
+    }
+
+    /// Updates hop count, previous node and bundle age before forwarding.
+    ///
+    /// Returns `False` if the hop limit or lifetime is exceeded.
+    fn update_extensions(
+        &mut self,
+        local_node: &Bound<'_, PyAny>,
+        residence_time: u64,
+    ) -> PyResult<bool> {
+        let ok = self
+            .inner
+            .update_extensions(eid_arg(local_node)?, Duration::from_millis(residence_time));
+        self.inner.calculate_crc();
+        Ok(ok)
+    }
+
+    #[getter]
+    fn previous_node(&self) -> Option<PyEndpointID> {
+        self.inner
+            .previous_node()
+            .map(|n| PyEndpointID { inner: n.clone() })
+    }
+
+    /// The hop limit and count as a tuple, if there is a hop count block.
+    #[getter]
+    fn hop_count(&self) -> Option<(u8, u8)> {
+        self.inner
+            .extension_block_by_type(HOP_COUNT_BLOCK)
+            .and_then(|b| b.hop_count_get())
+    }
+
+    /// The bundle age in milliseconds, if known.
+    #[getter]
+    fn age(&self) -> Option<u64> {
+        self.inner.age().map(|a| a.as_millis() as u64)
+    }
+
+    fn is_lifetime_exceeded(&self) -> bool {
+        self.inner.is_lifetime_exceeded()
+    }
+
+    fn is_administrative_record(&self) -> bool {
+        self.inner.is_administrative_record()
+    }
+
+    /// Parses the status report carried by this bundle, `None` if it is no status report.
+    fn status_report<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        if !self.inner.is_administrative_record() {
+            return Ok(None);
+        }
+        match self.inner.administrative_record().map_err(bp7_err)? {
+            AdministrativeRecord::BundleStatusReport(sr) => Ok(Some(status_report_to_py(py, &sr)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Bundle('{}')", self.inner)
+    }
+}
+
+/// Creates a status report bundle about `orig`, sent from `src`.
+///
+/// `statuses` are status information positions or names, e.g., `["received", "delivered"]`.
+/// Unless `force` is set, statuses not requested by `orig` are dropped and `Bp7Error` is
+/// raised if none remain.
+#[pyfunction]
+#[pyo3(name = "status_report_bundle", signature = (orig, src, statuses, reason = NO_INFORMATION, crc_type = crc::CRC_NO, force = false))]
+fn py_status_report_bundle(
+    orig: PyRef<'_, PyBundle>,
+    src: &Bound<'_, PyAny>,
+    statuses: Vec<Bound<'_, PyAny>>,
+    reason: StatusReportReason,
+    crc_type: CrcRawType,
+    force: bool,
+) -> PyResult<PyBundle> {
+    let src = eid_arg(src)?;
+    let crc_type = crc_arg(crc_type)?;
+    let items = statuses
+        .iter()
+        .map(status_arg)
+        .collect::<PyResult<Vec<_>>>()?;
+    let inner = status_report_bundle_with(&orig.inner, src, crc_type, &items, reason, force)
+        .map_err(bp7_err)?;
+    Ok(PyBundle { inner })
+}
+
+#[pyfunction]
+#[pyo3(name = "status_report_reason_name")]
+fn py_status_report_reason_name(reason: StatusReportReason) -> Option<&'static str> {
+    status_report_reason_name(reason)
+}
+
+#[pyfunction]
+#[pyo3(name = "status_information_name")]
+fn py_status_information_name(status: StatusInformationPos) -> Option<&'static str> {
+    status_information_name(status)
+}
+
+#[pymodule]
+fn bp7(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("Bp7Error", m.py().get_type::<Bp7Error>())?;
+    m.add_class::<PyEndpointID>()?;
+    m.add_class::<PyCanonicalBlock>()?;
+    m.add_class::<PyPrimaryBlockBuilder>()?;
+    m.add_class::<PyBundle>()?;
+    m.add_function(wrap_pyfunction!(py_status_report_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(py_status_report_reason_name, m)?)?;
+    m.add_function(wrap_pyfunction!(py_status_information_name, m)?)?;
+
+    m.add("CRC_NO", crc::CRC_NO)?;
+    m.add("CRC_16", crc::CRC_16)?;
+    m.add("CRC_32", crc::CRC_32)?;
+    m.add("PAYLOAD_BLOCK", PAYLOAD_BLOCK)?;
+    m.add("PREVIOUS_NODE_BLOCK", PREVIOUS_NODE_BLOCK)?;
+    m.add("BUNDLE_AGE_BLOCK", BUNDLE_AGE_BLOCK)?;
+    m.add("HOP_COUNT_BLOCK", HOP_COUNT_BLOCK)?;
+    for status in 0..MAX_STATUS_INFORMATION_POS {
+        if let Some(name) = status_information_name(status) {
+            m.add(format!("{}_BUNDLE", name.to_uppercase()), status)?;
+        }
+    }
+    for reason in NO_INFORMATION..=BLOCK_UNSUPPORTED {
+        if let Some(name) = status_report_reason_name(reason) {
+            m.add(name.to_uppercase(), reason)?;
+        }
+    }
+    Ok(())
+}
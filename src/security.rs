@@ -27,8 +27,7 @@ use serde::ser::{SerializeSeq, Serializer};
 use serde::{Deserialize, Deserializer, Serialize, de};
 
 // https://www.rfc-editor.org/rfc/rfc9172.html#BlockType
-pub const INTEGRITY_BLOCK: CanonicalBlockType = 11;
-pub const CONFIDENTIALITY_BLOCK: CanonicalBlockType = 12;
+pub use crate::canonical::{CONFIDENTIALITY_BLOCK, INTEGRITY_BLOCK};
 
 // SHA Variant
 // https://www.rfc-editor.org/rfc/rfc9173.html#name-sha-variant
@@ -208,7 +207,6 @@ impl IntegrityProtectedPlaintext {
         );
         ippt.append(&mut optional_ippt_data);
         ippt.append(&mut self.security_target_contents);
-        println!("ippt hex {:?}", hexify(&ippt));
         ippt
     }
 
@@ -396,6 +394,18 @@ pub enum IntegrityBlockBuilderError {
     FlagSetButNoParameter,
 }
 
+#[derive(Error, Debug)]
+pub enum IntegrityBlockError {
+    #[error("Block {0} is not a block integrity block")]
+    NotIntegrityBlock(u64),
+    #[error("Malformed abstract security block: {0}")]
+    Malformed(String),
+    #[error("Security target {0} not found in bundle")]
+    MissingTarget(u64),
+    #[error("Unsupported SHA variant {0}")]
+    UnsupportedShaVariant(ShaVariantType),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IntegrityBlockBuilder {
     security_targets: Option<Vec<u64>>, // array of block numbers TODO:  MUST represent the block number of a block that exists in the bundle
@@ -561,6 +571,76 @@ impl IntegrityBlock {
         }
     }
 
+    /// Decodes the block-type-specific data of a BIB, i.e., the CBOR sequence produced by `to_cbor`.
+    pub fn from_cbor(buf: &[u8]) -> Result<IntegrityBlock, IntegrityBlockError> {
+        let mut items = serde_cbor::Deserializer::from_slice(buf)
+            .into_iter::<serde_cbor::Value>()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| IntegrityBlockError::Malformed(e.to_string()))?;
+        // security context parameters are omitted if the context flags are unset
+        if items.len() == 5 {
+            items.insert(4, serde_cbor::Value::Null);
+        }
+        if items.len() != 6 {
+            return Err(IntegrityBlockError::Malformed(format!(
+                "expected 5 or 6 items, got {}",
+                items.len()
+            )));
+        }
+        serde_cbor::value::from_value(serde_cbor::Value::Array(items))
+            .map_err(|e| IntegrityBlockError::Malformed(e.to_string()))
+    }
+
+    /// Recomputes the MAC of every security target and compares it with the stored results.
+    ///
+    /// `block_number` is the number of the BIB carrying this integrity block within `bundle`.
+    pub fn verify(
+        &self,
+        bundle: &Bundle,
+        block_number: u64,
+        key_bytes: [u8; 16],
+    ) -> Result<bool, IntegrityBlockError> {
+        let bib_block = bundle
+            .canonicals
+            .iter()
+            .find(|b| b.block_number == block_number && b.block_type == INTEGRITY_BLOCK)
+            .ok_or(IntegrityBlockError::NotIntegrityBlock(block_number))?;
+
+        let mut params = self.security_context_parameters.clone().unwrap_or_default();
+        let sha_variant = params.sha_variant.get_or_insert((1, HMAC_SHA_384)).1;
+        if !(HMAC_SHA_256..=HMAC_SHA_512).contains(&sha_variant) {
+            return Err(IntegrityBlockError::UnsupportedShaVariant(sha_variant));
+        }
+        let scope_flags = params.integrity_scope_flags.get_or_insert((3, 0x0007)).1;
+
+        let mut ippt_list = Vec::new();
+        for target in &self.security_targets {
+            let target_block = bundle
+                .canonicals
+                .iter()
+                .find(|b| b.block_number == *target)
+                .ok_or(IntegrityBlockError::MissingTarget(*target))?;
+            let mut ippt = IpptBuilder::default()
+                .primary_block(bundle.primary.clone())
+                .security_header((INTEGRITY_BLOCK, block_number, bib_block.block_control_flags))
+                .scope_flags(scope_flags)
+                .build();
+            ippt_list.push((*target, ippt.create(target_block)));
+        }
+
+        let mut expected = self.clone();
+        expected.security_context_parameters = Some(params);
+        expected.compute_hmac(key_bytes, ippt_list.iter().map(|(t, i)| (*t, i)).collect());
+
+        let macs = |results: &Vec<Vec<(u64, ByteBuffer)>>| -> Vec<Option<ByteBuffer>> {
+            results
+                .iter()
+                .map(|r| r.first().map(|(_, mac)| mac.clone()))
+                .collect()
+        };
+        Ok(macs(&expected.security_results) == macs(&self.security_results))
+    }
+
     pub fn to_cbor(&self) -> ByteBuffer {
         let mut cbor_format = Vec::<u8>::new();
 
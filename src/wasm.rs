@@ -1,9 +1,15 @@
 use wasm_bindgen::prelude::*;
 
+use crate::administrative_record::*;
 use crate::bundle::Bundle;
+use crate::canonical::*;
+use crate::crc::{CrcBlock, CrcRawType};
 use crate::dtntime::CreationTimestamp;
 use crate::eid::*;
+use crate::flags::{BlockControlFlags, BundleControlFlags, flag_names};
+use crate::primary::PrimaryBlockBuilder;
 use core::convert::TryFrom;
+use core::time::Duration;
 
 /// Create a new standard bundle with current timestamp
 #[wasm_bindgen]
@@ -130,14 +136,14 @@ pub fn payload_from_cbor(buf: &[u8]) -> Result<Option<Vec<u8>>, JsValue> {
 pub fn valid_bundle(bundle_js: &JsValue) -> Result<bool, JsValue> {
     let bundle: Bundle = serde_wasm_bindgen::from_value(bundle_js.clone())
         .map_err(|e| JsValue::from_str(&format!("Deserialization error: {}", e)))?;
-    Ok(!bundle.primary.is_lifetime_exceeded() && bundle.validate().is_ok())
+    Ok(!bundle.is_lifetime_exceeded() && bundle.validate().is_ok())
 }
 
 /// Validate CBOR bytes as a bundle
 #[wasm_bindgen]
 pub fn valid_cbor(buf: &[u8]) -> Result<bool, JsValue> {
     match Bundle::try_from(buf.to_vec()) {
-        Ok(bundle) => Ok(!bundle.primary.is_lifetime_exceeded() && bundle.validate().is_ok()),
+        Ok(bundle) => Ok(!bundle.is_lifetime_exceeded() && bundle.validate().is_ok()),
         Err(_) => Ok(false),
     }
 }
@@ -157,3 +163,539 @@ pub fn cbor_is_administrative_record(buf: &[u8]) -> Result<bool, JsValue> {
         .map_err(|e| JsValue::from_str(&format!("CBOR decode error: {}", e)))?;
     Ok(bundle.is_administrative_record())
 }
+
+fn js_err(e: impl core::fmt::Display) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+fn parse_eid(eid: &str) -> Result<EndpointID, JsValue> {
+    EndpointID::try_from(eid).map_err(|e| js_err(format!("Invalid endpoint `{}`: {}", eid, e)))
+}
+
+fn check_crc_type(crc_type: CrcRawType) -> Result<CrcRawType, JsValue> {
+    crate::crc::validate_crc_type(crc_type).map_err(js_err)
+}
+
+/// Status information positions used in bundle status reports.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusInformation {
+    Received = 0,
+    Forwarded = 1,
+    Delivered = 2,
+    Deleted = 3,
+}
+
+/// Hop limit and current hop count of a hop count block.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HopCount {
+    pub limit: u8,
+    pub count: u8,
+}
+
+/// An endpoint ID, e.g., `dtn://node1/inbox` or `ipn:23.42`.
+#[wasm_bindgen(js_name = EndpointID)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsEndpointID {
+    inner: EndpointID,
+}
+
+#[wasm_bindgen(js_class = EndpointID)]
+impl JsEndpointID {
+    #[wasm_bindgen(constructor)]
+    pub fn new(eid: &str) -> Result<JsEndpointID, JsValue> {
+        Ok(JsEndpointID {
+            inner: parse_eid(eid)?,
+        })
+    }
+
+    /// The null endpoint `dtn:none`.
+    pub fn none() -> JsEndpointID {
+        JsEndpointID {
+            inner: EndpointID::none(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn scheme(&self) -> String {
+        self.inner.scheme()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn node(&self) -> Option<String> {
+        self.inner.node()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn service_name(&self) -> Option<String> {
+        self.inner.service_name()
+    }
+
+    pub fn is_node_id(&self) -> bool {
+        self.inner.is_node_id()
+    }
+
+    pub fn equals(&self, other: &JsEndpointID) -> bool {
+        self.inner == other.inner
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+/// Builder for the primary block of a new bundle.
+///
+/// The creation timestamp defaults to the time of building and the lifetime to one hour.
+/// Setters consume the builder, so calls have to be chained.
+#[wasm_bindgen(js_name = PrimaryBlockBuilder)]
+pub struct JsPrimaryBlockBuilder {
+    inner: PrimaryBlockBuilder,
+}
+
+impl Default for JsPrimaryBlockBuilder {
+    fn default() -> Self {
+        JsPrimaryBlockBuilder::new()
+    }
+}
+
+#[wasm_bindgen(js_class = PrimaryBlockBuilder)]
+impl JsPrimaryBlockBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> JsPrimaryBlockBuilder {
+        JsPrimaryBlockBuilder {
+            inner: PrimaryBlockBuilder::default(),
+        }
+    }
+
+    pub fn destination(self, eid: &str) -> Result<JsPrimaryBlockBuilder, JsValue> {
+        Ok(JsPrimaryBlockBuilder {
+            inner: self.inner.destination(parse_eid(eid)?),
+        })
+    }
+
+    pub fn source(self, eid: &str) -> Result<JsPrimaryBlockBuilder, JsValue> {
+        Ok(JsPrimaryBlockBuilder {
+            inner: self.inner.source(parse_eid(eid)?),
+        })
+    }
+
+    pub fn report_to(self, eid: &str) -> Result<JsPrimaryBlockBuilder, JsValue> {
+        Ok(JsPrimaryBlockBuilder {
+            inner: self.inner.report_to(parse_eid(eid)?),
+        })
+    }
+
+    /// Sets the bundle processing control flags.
+    pub fn flags(self, flags: u64) -> JsPrimaryBlockBuilder {
+        JsPrimaryBlockBuilder {
+            inner: self.inner.bundle_control_flags(flags),
+        }
+    }
+
+    /// Sets the creation timestamp, `time` is given in milliseconds since the DTN epoch.
+    pub fn creation_timestamp(self, time: u64, seqno: u64) -> JsPrimaryBlockBuilder {
+        JsPrimaryBlockBuilder {
+            inner: self
+                .inner
+                .creation_timestamp(CreationTimestamp::with_time_and_seq(time, seqno)),
+        }
+    }
+
+    /// Sets the lifetime in milliseconds.
+    pub fn lifetime(self, lifetime: u64) -> JsPrimaryBlockBuilder {
+        JsPrimaryBlockBuilder {
+            inner: self.inner.lifetime(Duration::from_millis(lifetime)),
+        }
+    }
+
+    /// Builds a bundle carrying `payload` with the given CRC type (0 = none, 1 = CRC16, 2 = CRC32).
+    pub fn build(self, payload: Vec<u8>, crc_type: CrcRawType) -> Result<JsBundle, JsValue> {
+        let crc_type = check_crc_type(crc_type)?;
+        let primary = self.inner.build().map_err(js_err)?;
+        let mut inner = Bundle::new(
+            primary,
+            vec![new_payload_block(BlockControlFlags::empty(), payload)],
+        );
+        inner.set_crc(crc_type);
+        inner.calculate_crc();
+        Ok(JsBundle { inner })
+    }
+}
+
+/// A bundle status report carried by an administrative record.
+#[wasm_bindgen(js_name = StatusReport)]
+pub struct JsStatusReport {
+    inner: StatusReport,
+}
+
+#[wasm_bindgen(js_class = StatusReport)]
+impl JsStatusReport {
+    /// All asserted status information positions.
+    #[wasm_bindgen(getter)]
+    pub fn asserted(&self) -> Vec<StatusInformation> {
+        [
+            StatusInformation::Received,
+            StatusInformation::Forwarded,
+            StatusInformation::Delivered,
+            StatusInformation::Deleted,
+        ]
+        .into_iter()
+        .filter(|s| self.inner.is_asserted(*s as StatusInformationPos))
+        .collect()
+    }
+
+    pub fn is_asserted(&self, status: StatusInformation) -> bool {
+        self.inner.is_asserted(status as StatusInformationPos)
+    }
+
+    /// Time of an asserted status in DTN time, if it was reported.
+    pub fn status_time(&self, status: StatusInformation) -> Option<u64> {
+        self.inner
+            .status_information
+            .get(status as usize)
+            .filter(|item| item.asserted && item.status_requested)
+            .map(|item| item.time)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn reason(&self) -> u32 {
+        self.inner.report_reason
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn reason_name(&self) -> Option<String> {
+        status_report_reason_name(self.inner.report_reason).map(String::from)
+    }
+
+    /// ID of the bundle this report refers to.
+    #[wasm_bindgen(getter)]
+    pub fn refbundle(&self) -> String {
+        self.inner.refbundle()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn source(&self) -> JsEndpointID {
+        JsEndpointID {
+            inner: self.inner.source_node.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn creation_time(&self) -> u64 {
+        self.inner.timestamp.dtntime()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sequence_number(&self) -> u64 {
+        self.inner.timestamp.seqno()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn frag_offset(&self) -> u64 {
+        self.inner.frag_offset
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn frag_len(&self) -> u64 {
+        self.inner.frag_len
+    }
+}
+
+/// A bundle with typed accessors, avoiding serde round-trips for every call.
+#[wasm_bindgen(js_name = Bundle)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsBundle {
+    inner: Bundle,
+}
+
+impl JsBundle {
+    fn add_block(&mut self, block: CanonicalBlock) -> Result<(), JsValue> {
+        self.inner.add_block(block).map(|_| ()).map_err(js_err)
+    }
+}
+
+#[wasm_bindgen(js_class = Bundle)]
+impl JsBundle {
+    /// Decodes a bundle from CBOR bytes without validating it.
+    pub fn from_cbor(buf: &[u8]) -> Result<JsBundle, JsValue> {
+        let inner = Bundle::try_from(buf)
+            .map_err(|e| JsValue::from_str(&format!("CBOR decode error: {}", e)))?;
+        Ok(JsBundle { inner })
+    }
+
+    pub fn to_cbor(&mut self) -> Vec<u8> {
+        self.inner.to_cbor()
+    }
+
+    /// Converts a bundle object as returned by `decode_from_cbor`.
+    pub fn from_js_value(bundle_js: JsValue) -> Result<JsBundle, JsValue> {
+        let inner: Bundle = serde_wasm_bindgen::from_value(bundle_js)
+            .map_err(|e| JsValue::from_str(&format!("Deserialization error: {}", e)))?;
+        Ok(JsBundle { inner })
+    }
+
+    /// Converts the bundle to the plain object representation used by `encode_to_cbor`.
+    pub fn to_js_value(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.inner)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> String {
+        self.inner.id()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn source(&self) -> JsEndpointID {
+        JsEndpointID {
+            inner: self.inner.primary.source.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn destination(&self) -> JsEndpointID {
+        JsEndpointID {
+            inner: self.inner.primary.destination.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn report_to(&self) -> JsEndpointID {
+        JsEndpointID {
+            inner: self.inner.primary.report_to.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn creation_time(&self) -> u64 {
+        self.inner.primary.creation_timestamp.dtntime()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sequence_number(&self) -> u64 {
+        self.inner.primary.creation_timestamp.seqno()
+    }
+
+    /// Bundle processing control flags.
+    #[wasm_bindgen(getter)]
+    pub fn flags(&self) -> u64 {
+        self.inner.primary.bundle_control_flags
+    }
+
+    /// Names of the set bundle processing control flags.
+    #[wasm_bindgen(getter)]
+    pub fn flag_names(&self) -> Vec<String> {
+        flag_names(BundleControlFlags::from_bits_retain(
+            self.inner.primary.bundle_control_flags,
+        ))
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_flags(&mut self, flags: u64) {
+        self.inner.primary.bundle_control_flags = flags;
+        self.inner.calculate_crc();
+    }
+
+    /// Lifetime in milliseconds.
+    #[wasm_bindgen(getter)]
+    pub fn lifetime(&self) -> u64 {
+        self.inner.primary.lifetime.as_millis() as u64
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_lifetime(&mut self, lifetime: u64) {
+        self.inner.primary.lifetime = Duration::from_millis(lifetime);
+        self.inner.calculate_crc();
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn crc_type(&self) -> CrcRawType {
+        self.inner.primary.crc_type()
+    }
+
+    /// Sets the CRC type of all blocks (0 = none, 1 = CRC16, 2 = CRC32).
+    pub fn set_crc(&mut self, crc_type: CrcRawType) -> Result<(), JsValue> {
+        self.inner.set_crc(check_crc_type(crc_type)?);
+        self.inner.calculate_crc();
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn payload(&self) -> Option<Vec<u8>> {
+        self.inner.payload().cloned()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_payload(&mut self, payload: Vec<u8>) {
+        self.inner.set_payload(payload);
+        self.inner.calculate_crc();
+    }
+
+    /// Block numbers of all canonical blocks.
+    #[wasm_bindgen(getter)]
+    pub fn block_numbers(&self) -> Vec<u64> {
+        self.inner
+            .canonicals
+            .iter()
+            .map(|b| b.block_number)
+            .collect()
+    }
+
+    /// Adds an extension block with raw block-type-specific data.
+    pub fn add_extension_block(
+        &mut self,
+        block_type: u64,
+        flags: u8,
+        data: Vec<u8>,
+    ) -> Result<(), JsValue> {
+        if [
+            PAYLOAD_BLOCK,
+            HOP_COUNT_BLOCK,
+            BUNDLE_AGE_BLOCK,
+            PREVIOUS_NODE_BLOCK,
+        ]
+        .contains(&block_type)
+        {
+            return Err(js_err(format!(
+                "Block type {} has a dedicated method",
+                block_type
+            )));
+        }
+        self.add_block(new_canonical_block(
+            block_type,
+            0,
+            flags,
+            CanonicalData::Unknown(data),
+        ))
+    }
+
+    /// Adds a hop count block with the given limit (1 to 255).
+    pub fn add_hop_count_block(&mut self, limit: u8) -> Result<(), JsValue> {
+        if limit == 0 {
+            return Err(js_err("Hop limit must be between 1 and 255"));
+        }
+        self.add_block(new_hop_count_block(0, BlockControlFlags::empty(), limit))
+    }
+
+    /// Adds a bundle age block, `age` is given in milliseconds.
+    pub fn add_bundle_age_block(&mut self, age: u64) -> Result<(), JsValue> {
+        self.add_block(new_bundle_age_block(0, BlockControlFlags::empty(), age))
+    }
+
+    pub fn add_previous_node_block(&mut self, node: &str) -> Result<(), JsValue> {
+        let node = parse_eid(node)?;
+        self.add_block(new_previous_node_block(0, BlockControlFlags::empty(), node))
+    }
+
+    /// Removes the block with the given number, the payload block cannot be removed.
+    pub fn remove_block(&mut self, block_number: u64) -> bool {
+        let removed = self.inner.remove_block(block_number).is_some();
+        self.inner.calculate_crc();
+        removed
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hop_count(&self) -> Option<HopCount> {
+        self.inner
+            .extension_block_by_type(HOP_COUNT_BLOCK)
+            .and_then(|b| b.hop_count_get())
+            .map(|(limit, count)| HopCount { limit, count })
+    }
+
+    /// Description of the bundle, a single line or one field per line if `multiline` is set.
+    pub fn summary(&self, multiline: bool) -> String {
+        let summary = self.inner.summary();
+        if multiline {
+            format!("{:#}", summary)
+        } else {
+            summary.to_string()
+        }
+    }
+
+    /// Bundle age in milliseconds, if known.
+    #[wasm_bindgen(getter)]
+    pub fn age(&self) -> Option<u64> {
+        self.inner.age().map(|a| a.as_millis() as u64)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn previous_node(&self) -> Option<JsEndpointID> {
+        self.inner
+            .previous_node()
+            .map(|n| JsEndpointID { inner: n.clone() })
+    }
+
+    /// Updates hop count, previous node and bundle age before forwarding.
+    ///
+    /// Returns `false` if the hop limit or lifetime is exceeded.
+    pub fn update_extensions(
+        &mut self,
+        local_node: &str,
+        residence_time: u64,
+    ) -> Result<bool, JsValue> {
+        let ok = self.inner.update_extensions(
+            parse_eid(local_node)?,
+            Duration::from_millis(residence_time),
+        );
+        self.inner.calculate_crc();
+        Ok(ok)
+    }
+
+    /// Throws an error listing all validation errors if the bundle is invalid.
+    pub fn validate(&self) -> Result<(), JsValue> {
+        self.inner.validate().map_err(|errs| {
+            let msgs: Vec<String> = errs.iter().map(|e| e.to_string()).collect();
+            js_err(msgs.join("\n"))
+        })
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.inner.validate().is_ok()
+    }
+
+    pub fn is_lifetime_exceeded(&self) -> bool {
+        self.inner.is_lifetime_exceeded()
+    }
+
+    /// Named to avoid a symbol clash with the `bundle_is_administrative_record` function.
+    pub fn is_admin_record(&self) -> bool {
+        self.inner.is_administrative_record()
+    }
+
+    /// Parses the carried status report, `undefined` if this is no status report.
+    pub fn status_report(&self) -> Result<Option<JsStatusReport>, JsValue> {
+        if !self.inner.is_administrative_record() {
+            return Ok(None);
+        }
+        match self.inner.administrative_record().map_err(js_err)? {
+            AdministrativeRecord::BundleStatusReport(inner) => Ok(Some(JsStatusReport { inner })),
+            _ => Ok(None),
+        }
+    }
+
+    /// Creates a status report about this bundle, sent from `src`.
+    ///
+    /// Unless `force` is set, statuses not requested by this bundle are dropped and an
+    /// error is thrown if none remain.
+    pub fn new_status_report(
+        &self,
+        src: &str,
+        statuses: Vec<StatusInformation>,
+        reason: u32,
+        crc_type: CrcRawType,
+        force: bool,
+    ) -> Result<JsBundle, JsValue> {
+        let src = parse_eid(src)?;
+        let crc_type = check_crc_type(crc_type)?;
+        let items: Vec<StatusInformationPos> = statuses
+            .iter()
+            .map(|s| *s as StatusInformationPos)
+            .collect();
+        let inner = status_report_bundle_with(&self.inner, src, crc_type, &items, reason, force)
+            .map_err(js_err)?;
+        Ok(JsBundle { inner })
+    }
+}
@@ -87,3 +87,497 @@ fn status_report_tests() {
         BundleControlFlags::BUNDLE_ADMINISTRATIVE_RECORD_PAYLOAD.bits();
     assert!(bndl.is_administrative_record()); // actually not true since no payload block has been added
 }
+
+#[test]
+fn bibe_tests() {
+    let mut inner = new_complete_bundle(crc::CRC_32);
+    let outer_src = eid::EndpointID::try_from("dtn://tunnel-in/").unwrap();
+    let outer_dst = eid::EndpointID::try_from("dtn://tunnel-out/bibe").unwrap();
+
+    let mut outer = new_bibe_bundle(
+        &mut inner,
+        outer_src.clone(),
+        outer_dst.clone(),
+        23,
+        dtntime::dtn_time_now() + 60_000,
+        crc::CRC_16,
+    );
+    assert!(outer.is_administrative_record());
+    assert_eq!(outer.primary.destination, outer_dst);
+
+    let outer: Bundle = outer.to_cbor().try_into().unwrap();
+    assert!(outer.validate().is_ok());
+    let bpdu = BibePdu::from_bundle(&outer).unwrap();
+    assert!(bpdu.custody_requested());
+    assert_eq!(bpdu.transmission_id, 23);
+    assert_eq!(bpdu.bundle().unwrap(), inner);
+
+    let bndl = new_complete_bundle(crc::CRC_NO);
+    assert!(BibePdu::from_bundle(&bndl).is_err());
+}
+
+#[test]
+fn bibe_encapsulate_tests() {
+    let mut inner = new_complete_bundle(crc::CRC_32);
+    let outer_src = eid::EndpointID::try_from("dtn://tunnel-in/").unwrap();
+    let outer_dst = eid::EndpointID::try_from("dtn://tunnel-out/bibe").unwrap();
+
+    let outer = inner
+        .encapsulate(outer_src.clone(), outer_dst.clone(), crc::CRC_16)
+        .unwrap();
+    assert!(outer.validate().is_ok());
+    assert_eq!(outer.primary.source, outer_src);
+    assert_eq!(outer.primary.destination, outer_dst);
+    assert!(outer.primary.lifetime <= inner.primary.lifetime);
+    assert!(!BibePdu::from_bundle(&outer).unwrap().custody_requested());
+
+    let outer: Bundle = outer.clone().to_cbor().try_into().unwrap();
+    assert_eq!(outer.decapsulate().unwrap(), inner);
+
+    // not a BIBE bundle
+    assert!(matches!(
+        inner.decapsulate(),
+        Err(BibeError::AdministrativeRecord(
+            AdministrativeRecordError::NotAdministrativeRecord
+        ))
+    ));
+
+    // expired inner bundle
+    let mut expired = new_complete_bundle(crc::CRC_NO);
+    expired.primary.creation_timestamp = dtntime::CreationTimestamp::with_time_and_seq(0, 0);
+    expired
+        .extension_block_by_type_mut(canonical::BUNDLE_AGE_BLOCK)
+        .unwrap()
+        .set_data(canonical::CanonicalData::BundleAge(60 * 60 * 1000));
+    assert!(matches!(
+        expired.encapsulate(outer_src.clone(), outer_dst.clone(), crc::CRC_NO),
+        Err(BibeError::Expired(_))
+    ));
+    let outer = new_bibe_bundle(
+        &mut expired,
+        outer_src.clone(),
+        outer_dst.clone(),
+        0,
+        0,
+        crc::CRC_NO,
+    );
+    assert!(matches!(outer.decapsulate(), Err(BibeError::Expired(_))));
+
+    // invalid inner bundle
+    let mut invalid = new_complete_bundle(crc::CRC_NO);
+    invalid.canonicals[1].block_number = 2;
+    let outer = invalid
+        .encapsulate(outer_src, outer_dst, crc::CRC_NO)
+        .unwrap();
+    assert!(matches!(outer.decapsulate(), Err(BibeError::Invalid(_, _))));
+}
+
+#[test]
+fn custody_signal_tests() {
+    let mut cs = CustodySignal::new();
+    for id in [1, 2, 3, 7, 5, 6] {
+        cs.add(CUSTODY_ACCEPTED, id).unwrap();
+    }
+    cs.add(CUSTODY_DEPLETED_STORAGE, 10).unwrap();
+    // already signaled, must not move to another disposition
+    cs.add(CUSTODY_REDUNDANT, 2).unwrap();
+
+    assert_eq!(
+        cs.dispositions[0],
+        (
+            CUSTODY_ACCEPTED,
+            vec![SequenceRange::new(1, 3), SequenceRange::new(5, 3)]
+        )
+    );
+    assert_eq!(
+        cs.transmission_ids(CUSTODY_ACCEPTED).collect::<Vec<_>>(),
+        vec![1, 2, 3, 5, 6, 7]
+    );
+    assert_eq!(cs.disposition_of(10), Some(CUSTODY_DEPLETED_STORAGE));
+    assert_eq!(cs.disposition_of(4), None);
+
+    let adm = AdministrativeRecord::CustodySignal(cs.clone());
+    let encoded = serde_cbor::to_vec(&adm).unwrap();
+    let decoded: AdministrativeRecord = serde_cbor::from_slice(&encoded).unwrap();
+    assert_eq!(adm, decoded);
+
+    let mut inner = new_complete_bundle(crc::CRC_NO);
+    let bpdu_bundle = new_bibe_bundle(
+        &mut inner,
+        "dtn://tunnel-in/".try_into().unwrap(),
+        "dtn://tunnel-out/bibe".try_into().unwrap(),
+        1,
+        0,
+        crc::CRC_NO,
+    );
+    let signal = new_custody_signal_bundle(
+        &bpdu_bundle,
+        "dtn://tunnel-out/".try_into().unwrap(),
+        crc::CRC_NO,
+        cs,
+    );
+    assert_eq!(signal.primary.destination, bpdu_bundle.primary.source);
+    assert!(signal.validate().is_ok());
+
+    // hostile ranges exceeding the ID space or overlapping each other are rejected
+    let mut hostile = CustodySignal::new();
+    hostile.dispositions.push((
+        CUSTODY_ACCEPTED,
+        vec![SequenceRange::new(u64::MAX - 1, u64::MAX)],
+    ));
+    let encoded = serde_cbor::to_vec(&hostile).unwrap();
+    assert!(serde_cbor::from_slice::<CustodySignal>(&encoded).is_err());
+    let encoded = serde_cbor::to_vec(&AdministrativeRecord::CustodySignal(hostile)).unwrap();
+    assert!(matches!(
+        serde_cbor::from_slice(&encoded).unwrap(),
+        AdministrativeRecord::Mismatched(CUSTODY_SIGNAL_TYPE_CODE, _)
+    ));
+
+    let mut overlapping = CustodySignal::new();
+    overlapping
+        .dispositions
+        .push((CUSTODY_ACCEPTED, vec![SequenceRange::new(0, 10)]));
+    overlapping
+        .dispositions
+        .push((CUSTODY_REDUNDANT, vec![SequenceRange::new(9, 1)]));
+    let encoded = serde_cbor::to_vec(&overlapping).unwrap();
+    assert!(serde_cbor::from_slice::<CustodySignal>(&encoded).is_err());
+
+    // a range reaching the end of the ID space is accepted, but cannot grow beyond it
+    let mut full = CustodySignal::new();
+    full.dispositions
+        .push((CUSTODY_ACCEPTED, vec![SequenceRange::new(0, u64::MAX)]));
+    let encoded = serde_cbor::to_vec(&full).unwrap();
+    let mut cs: CustodySignal = serde_cbor::from_slice(&encoded).unwrap();
+    assert_eq!(cs, full);
+    assert_eq!(
+        cs.add(CUSTODY_ACCEPTED, u64::MAX),
+        Err(CustodySignalError::RangeOverflow(0))
+    );
+    assert_eq!(cs, full);
+    cs.add(CUSTODY_REDUNDANT, u64::MAX).unwrap();
+    assert_eq!(cs.disposition_of(u64::MAX), Some(CUSTODY_REDUNDANT));
+}
+
+#[test]
+fn status_report_policy_tests() {
+    let src: EndpointID = "dtn://node3/".try_into().unwrap();
+    let mut bndl = new_complete_bundle(crc::CRC_NO);
+    bndl.primary.bundle_control_flags = (BundleControlFlags::BUNDLE_STATUS_REQUEST_RECEPTION
+        | BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY)
+        .bits();
+
+    assert!(is_status_requested(&bndl, RECEIVED_BUNDLE));
+    assert!(!is_status_requested(&bndl, FORWARDED_BUNDLE));
+    assert_eq!(
+        check_status_report_policy(&bndl, FORWARDED_BUNDLE),
+        Err(StatusReportError::NotRequested(FORWARDED_BUNDLE))
+    );
+    assert_eq!(
+        check_status_report_policy(&bndl, MAX_STATUS_INFORMATION_POS),
+        Err(StatusReportError::InvalidStatusInformation(
+            MAX_STATUS_INFORMATION_POS
+        ))
+    );
+
+    // unrequested forwarding assertion is dropped, the others are combined
+    let report = status_report_bundle(
+        &bndl,
+        src.clone(),
+        crc::CRC_NO,
+        &[RECEIVED_BUNDLE, FORWARDED_BUNDLE, DELIVERED_BUNDLE],
+        NO_INFORMATION,
+    )
+    .unwrap();
+    assert_eq!(report.primary.destination, bndl.primary.report_to);
+    let adm: AdministrativeRecord = serde_cbor::from_slice(report.payload().unwrap()).unwrap();
+    if let AdministrativeRecord::BundleStatusReport(sr) = adm {
+        assert!(sr.is_asserted(RECEIVED_BUNDLE));
+        assert!(!sr.is_asserted(FORWARDED_BUNDLE));
+        assert!(sr.is_asserted(DELIVERED_BUNDLE));
+        assert!(!sr.is_asserted(DELETED_BUNDLE));
+        assert_eq!(sr.refbundle(), bndl.id());
+    } else {
+        panic!("Not a status report!");
+    }
+
+    assert_eq!(
+        status_report_bundle(&bndl, src.clone(), crc::CRC_NO, &[DELETED_BUNDLE], 0).unwrap_err(),
+        StatusReportError::NotRequested(DELETED_BUNDLE)
+    );
+    assert_eq!(
+        status_report_bundle(&bndl, src.clone(), crc::CRC_NO, &[], 0).unwrap_err(),
+        StatusReportError::NoAssertion
+    );
+
+    // forcing skips the policy checks and reports all assertions
+    let forced =
+        status_report_bundle_with(&bndl, src.clone(), crc::CRC_NO, &[DELETED_BUNDLE], 0, true)
+            .unwrap();
+    let adm: AdministrativeRecord = serde_cbor::from_slice(forced.payload().unwrap()).unwrap();
+    if let AdministrativeRecord::BundleStatusReport(sr) = adm {
+        assert!(sr.is_asserted(DELETED_BUNDLE));
+    } else {
+        panic!("Not a status report!");
+    }
+    assert_eq!(
+        status_report_bundle_with(&bndl, src.clone(), crc::CRC_NO, &[DELETED_BUNDLE], 0, false)
+            .unwrap_err(),
+        StatusReportError::NotRequested(DELETED_BUNDLE)
+    );
+
+    let mut no_report_to = bndl.clone();
+    no_report_to.primary.report_to = EndpointID::none();
+    assert_eq!(
+        check_status_report_policy(&no_report_to, RECEIVED_BUNDLE),
+        Err(StatusReportError::ReportToNone)
+    );
+    // forcing a report does not help without a report-to endpoint
+    assert_eq!(
+        status_report_bundle_with(
+            &no_report_to,
+            src.clone(),
+            crc::CRC_NO,
+            &[RECEIVED_BUNDLE],
+            0,
+            true
+        )
+        .unwrap_err(),
+        StatusReportError::ReportToNone
+    );
+
+    let mut anonymous = bndl.clone();
+    anonymous.primary.source = EndpointID::none();
+    assert_eq!(
+        check_status_report_policy(&anonymous, RECEIVED_BUNDLE),
+        Err(StatusReportError::AnonymousSource)
+    );
+
+    assert_eq!(
+        status_report_bundle(&report, src, crc::CRC_NO, &[RECEIVED_BUNDLE], 0).unwrap_err(),
+        StatusReportError::AdministrativeRecord
+    );
+}
+
+#[test]
+fn status_report_fragment_tests() {
+    let mut bndl = new_complete_bundle(crc::CRC_NO);
+    bndl.primary.bundle_control_flags = (BundleControlFlags::BUNDLE_IS_FRAGMENT
+        | BundleControlFlags::BUNDLE_STATUS_REQUEST_RECEPTION)
+        .bits();
+    bndl.primary.fragmentation_offset = 10;
+    bndl.primary.total_data_length = 13;
+
+    let sr = new_status_report(&bndl, RECEIVED_BUNDLE, NO_INFORMATION);
+    assert_eq!(sr.frag_offset, 10);
+    assert_eq!(sr.frag_len, 3);
+    assert_eq!(sr.refbundle(), bndl.id());
+
+    let encoded = serde_cbor::to_vec(&sr).unwrap();
+    let decoded: StatusReport = serde_cbor::from_slice(&encoded).unwrap();
+    assert_eq!(sr, decoded);
+}
+
+#[test]
+fn administrative_record_mismatch_tests() {
+    let mut bndl = new_complete_bundle(crc::CRC_NO);
+    bndl.primary.bundle_control_flags = BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY.bits();
+    assert_eq!(
+        bndl.administrative_record().unwrap_err(),
+        AdministrativeRecordError::NotAdministrativeRecord
+    );
+    assert!(bndl.check_administrative_record().is_ok());
+
+    let report = new_status_report_bundle(
+        &bndl,
+        "dtn://node3/".try_into().unwrap(),
+        crc::CRC_NO,
+        DELIVERED_BUNDLE,
+        NO_INFORMATION,
+    )
+    .unwrap();
+    assert!(matches!(
+        report.administrative_record(),
+        Ok(AdministrativeRecord::BundleStatusReport(_))
+    ));
+    assert!(report.check_administrative_record().is_ok());
+
+    // status report payload without the administrative record flag
+    let mut unflagged = report.clone();
+    unflagged.primary.bundle_control_flags = 0;
+    assert_eq!(
+        unflagged.check_administrative_record().unwrap_err(),
+        AdministrativeRecordError::Unflagged(BUNDLE_STATUS_REPORT_TYPE_CODE)
+    );
+
+    // flagged, but the payload is plain data
+    let mut mismatched = report.clone();
+    mismatched.set_payload(b"ABC".to_vec());
+    assert!(matches!(
+        mismatched.administrative_record(),
+        Err(AdministrativeRecordError::Mismatched(_))
+    ));
+
+    // status report type code with garbage content
+    let mut malformed = report.clone();
+    malformed.set_payload(serde_cbor::to_vec(&(1, "not a report")).unwrap());
+    assert_eq!(
+        malformed.administrative_record().unwrap_err(),
+        AdministrativeRecordError::MalformedStatusReport
+    );
+    let adm: AdministrativeRecord = serde_cbor::from_slice(malformed.payload().unwrap()).unwrap();
+    assert!(matches!(adm, AdministrativeRecord::Mismatched(1, _)));
+    assert_eq!(
+        serde_cbor::to_vec(&adm).unwrap(),
+        malformed.payload().unwrap().clone()
+    );
+    assert_eq!(
+        AdministrativeRecord::from_cbor(malformed.payload().unwrap()).unwrap(),
+        adm
+    );
+
+    // content is the received item, written back verbatim even if not encoded canonically
+    let received = vec![0x82, 0x01, 0x18, 0x05];
+    let adm = AdministrativeRecord::from_cbor(&received).unwrap();
+    assert_eq!(adm, AdministrativeRecord::Mismatched(1, vec![0x18, 0x05]));
+    assert_eq!(adm.to_cbor(), received);
+    assert_eq!(adm.to_payload().data(), &CanonicalData::Data(received));
+
+    // content is always an encoded item and never taken as a byte string
+    let adm = AdministrativeRecord::Mismatched(1, vec![0x01]);
+    let encoded = serde_cbor::to_vec(&adm).unwrap();
+    assert_eq!(encoded, vec![0x82, 0x01, 0x01]);
+    assert_eq!(adm.to_cbor(), encoded);
+    assert_eq!(AdministrativeRecord::from_cbor(&encoded).unwrap(), adm);
+    assert_eq!(
+        serde_cbor::from_slice::<AdministrativeRecord>(&encoded).unwrap(),
+        adm
+    );
+
+    // hand-built records with content that is not CBOR are written verbatim
+    let adm = AdministrativeRecord::Mismatched(1, vec![0xff, 0x00]);
+    assert!(serde_cbor::to_vec(&adm).is_err());
+    assert_eq!(adm.to_cbor(), vec![0x82, 0x01, 0xff, 0x00]);
+    assert_eq!(
+        adm.to_payload().data(),
+        &CanonicalData::Data(vec![0x82, 0x01, 0xff, 0x00])
+    );
+
+    malformed.set_payload(serde_cbor::to_vec(&(4, 23)).unwrap());
+    assert_eq!(
+        malformed.administrative_record().unwrap_err(),
+        AdministrativeRecordError::Malformed(CUSTODY_SIGNAL_TYPE_CODE)
+    );
+}
+
+#[test]
+fn status_report_tracker_tests() {
+    let reporter: EndpointID = "dtn://node2/".try_into().unwrap();
+    let mut tracker = StatusReportTracker::new();
+
+    let mut delivered = new_complete_bundle(crc::CRC_NO);
+    delivered.primary.bundle_control_flags = (BundleControlFlags::BUNDLE_STATUS_REQUEST_RECEPTION
+        | BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY
+        | BundleControlFlags::BUNDLE_STATUS_REQUEST_DELETION
+        | BundleControlFlags::BUNDLE_REQUEST_STATUS_TIME)
+        .bits();
+    let mut deleted = delivered.clone();
+    deleted.primary.creation_timestamp =
+        CreationTimestamp::with_time_and_seq(delivered.primary.creation_timestamp.dtntime(), 1);
+    let mut lost = delivered.clone();
+    lost.primary.creation_timestamp =
+        CreationTimestamp::with_time_and_seq(delivered.primary.creation_timestamp.dtntime(), 2);
+
+    tracker.register(&delivered);
+    tracker.register(&deleted);
+    tracker.register(&lost);
+    assert_eq!(tracker.len(), 3);
+    assert_eq!(tracker.state(&lost.id()), Some(BundleState::Pending));
+
+    let reports = vec![
+        new_status_report_bundle(
+            &delivered,
+            reporter.clone(),
+            crc::CRC_NO,
+            DELIVERED_BUNDLE,
+            0,
+        )
+        .unwrap(),
+        // late reception report must not move the bundle back
+        new_status_report_bundle(
+            &delivered,
+            reporter.clone(),
+            crc::CRC_NO,
+            RECEIVED_BUNDLE,
+            0,
+        )
+        .unwrap(),
+        new_status_report_bundle(&deleted, reporter.clone(), crc::CRC_NO, RECEIVED_BUNDLE, 0)
+            .unwrap(),
+        new_status_report_bundle(
+            &deleted,
+            reporter.clone(),
+            crc::CRC_NO,
+            DELETED_BUNDLE,
+            HOP_LIMIT_EXCEEDED,
+        )
+        .unwrap(),
+    ];
+    for r in &reports {
+        assert!(tracker.ingest(r).unwrap().is_some());
+    }
+    assert!(tracker.ingest(&delivered).is_err());
+
+    let tracked = tracker.get(&delivered.id()).unwrap();
+    assert_eq!(tracked.state, BundleState::Delivered);
+    assert!(tracked.is_asserted(RECEIVED_BUNDLE));
+    assert!(tracked.assertions[0].time.is_some());
+    assert_eq!(tracked.assertions[0].reporter, reporter);
+    assert_eq!(
+        tracker.state(&deleted.id()),
+        Some(BundleState::Deleted(HOP_LIMIT_EXCEEDED))
+    );
+
+    // untracked bundles are ignored
+    let mut other = delivered.clone();
+    other.primary.creation_timestamp = CreationTimestamp::with_time_and_seq(1, 0);
+    let r = new_status_report_bundle(&other, reporter, crc::CRC_NO, DELIVERED_BUNDLE, 0).unwrap();
+    assert_eq!(tracker.ingest(&r).unwrap(), None);
+
+    assert!(tracker.expire(dtntime::dtn_time_now()).is_empty());
+    let expired = tracker.expire(lost.primary.creation_timestamp.dtntime() + 60 * 60 * 1000);
+    assert_eq!(expired, vec![lost.id()]);
+    assert_eq!(tracker.state(&lost.id()), Some(BundleState::Expired));
+
+    assert_eq!(tracker.drain_finished().len(), 3);
+    assert!(tracker.is_empty());
+
+    // lifetimes beyond the DTN time range saturate instead of overflowing
+    let mut forever = lost.clone();
+    forever.primary.lifetime = Duration::MAX;
+    tracker.register(&forever);
+    assert_eq!(tracker.get(&forever.id()).unwrap().expires, u64::MAX);
+    assert!(tracker.expire(u64::MAX - 1).is_empty());
+}
+
+#[test]
+fn status_report_name_tests() {
+    assert_eq!(status_information_name(DELIVERED_BUNDLE), Some("delivered"));
+    assert_eq!(status_information_name(MAX_STATUS_INFORMATION_POS), None);
+    for status in 0..MAX_STATUS_INFORMATION_POS {
+        let name = status_information_name(status).unwrap();
+        assert_eq!(status_information_from_name(name), Some(status));
+    }
+    assert_eq!(status_information_from_name("lost"), None);
+
+    assert_eq!(
+        status_report_reason_name(HOP_LIMIT_EXCEEDED),
+        Some("hop_limit_exceeded")
+    );
+    assert_eq!(status_report_reason_name(BLOCK_UNSUPPORTED + 1), None);
+    for reason in NO_INFORMATION..=BLOCK_UNSUPPORTED {
+        let name = status_report_reason_name(reason).unwrap();
+        assert_eq!(status_report_reason_from_name(name), Some(reason));
+    }
+    assert_eq!(status_report_reason_from_name("bogus"), None);
+}
@@ -1,3 +1,5 @@
+use bp7::bundle::Block;
+use bp7::crc::CrcBlock;
 use bp7::flags::*;
 use bp7::*;
 use std::convert::TryFrom;
@@ -195,7 +197,10 @@ fn bundle_canonical_update_tests() {
     assert!(hcb2.hop_count_get().unwrap() == (16, 1));
 
     let mut bndl = new_complete_bundle(crc::CRC_NO);
-    assert!(bndl.update_extensions("dtn://newnode".try_into().unwrap(), 23));
+    assert!(bndl.update_extensions(
+        "dtn://newnode".try_into().unwrap(),
+        Duration::from_millis(23)
+    ));
 
     let cb = bndl.extension_block_by_type_mut(HOP_COUNT_BLOCK).unwrap();
     assert!(cb.hop_count_get().unwrap() == (16, 1));
@@ -255,3 +260,426 @@ fn bundle_add_cblock() {
     b.set_payload("123".into());
     assert_eq!(b.payload().unwrap(), b"123");
 }
+
+#[test]
+fn bundle_lifetime_tests() {
+    let mut bndl = new_complete_bundle(crc::CRC_NO);
+    let remaining = bndl.remaining_lifetime().unwrap();
+    assert!(remaining <= Duration::from_secs(60 * 60));
+    assert!(remaining > Duration::from_secs(60 * 59));
+    assert!(!bndl.is_lifetime_exceeded());
+
+    bndl.primary.creation_timestamp =
+        CreationTimestamp::with_time_and_seq(dtntime::dtn_time_now() - 60 * 60 * 1000, 0);
+    assert_eq!(bndl.remaining_lifetime(), Some(Duration::ZERO));
+    assert!(bndl.is_lifetime_exceeded());
+    assert!(!bndl.update_extensions("dtn://newnode".try_into().unwrap(), Duration::ZERO));
+
+    // age-based lifetime for sources without a clock
+    let mut bndl = new_complete_bundle(crc::CRC_NO);
+    bndl.primary.creation_timestamp = CreationTimestamp::with_time_and_seq(0, 0);
+    assert_eq!(bndl.age(), Some(Duration::ZERO));
+    assert!(bndl.update_extensions(
+        "dtn://newnode".try_into().unwrap(),
+        Duration::from_secs(60 * 30)
+    ));
+    assert_eq!(bndl.age(), Some(Duration::from_secs(60 * 30)));
+    assert_eq!(
+        bndl.remaining_lifetime(),
+        Some(Duration::from_secs(60 * 30))
+    );
+    assert!(!bndl.update_extensions(
+        "dtn://newnode".try_into().unwrap(),
+        Duration::from_secs(60 * 30)
+    ));
+    assert!(bndl.is_lifetime_exceeded());
+}
+
+#[test]
+fn bundle_age_block_added_tests() {
+    let mut bndl = new_complete_bundle(crc::CRC_16);
+    bndl.canonicals.retain(|c| c.block_type != BUNDLE_AGE_BLOCK);
+    assert_eq!(bndl.age().map(|a| a < Duration::from_secs(1)), Some(true));
+    assert!(!bndl.ensure_bundle_age_block());
+
+    bndl.primary.creation_timestamp = CreationTimestamp::with_time_and_seq(0, 0);
+    assert!(bndl.validate().is_err());
+    assert_eq!(bndl.age(), None);
+    assert_eq!(bndl.remaining_lifetime(), None);
+
+    assert!(bndl.update_extensions(
+        "dtn://newnode".try_into().unwrap(),
+        Duration::from_millis(1500)
+    ));
+    let bablock = bndl.extension_block_by_type(BUNDLE_AGE_BLOCK).unwrap();
+    assert_eq!(bablock.bundle_age(), Some(Duration::from_millis(1500)));
+    assert_eq!(bablock.bundle_age_get(), Some(1500));
+    assert_eq!(bablock.crc_type(), crc::CRC_16);
+    assert_eq!(bndl.canonicals.last().unwrap().block_type, PAYLOAD_BLOCK);
+
+    bndl.calculate_crc();
+    assert!(bndl.validate().is_ok());
+}
+
+#[test]
+fn cbor_diag_tests() {
+    assert_eq!(
+        helpers::cbor_diag(&helpers::unhexify("9f0118ff").unwrap()),
+        None,
+        "truncated items must be rejected"
+    );
+    assert_eq!(
+        helpers::cbor_diag(&helpers::unhexify("9f01820203a1616142ff00ff").unwrap()).as_deref(),
+        Some("[_ 1, [2, 3], {\"a\": h'ff00'}]")
+    );
+    assert_eq!(
+        helpers::cbor_diag(&helpers::unhexify("8518171901f4f5f6f93e00").unwrap()).as_deref(),
+        Some("[23_0, 500, true, null, 1.5]")
+    );
+
+    // deeply nested items are rejected instead of overflowing the stack
+    let nested = |depth: usize| {
+        let mut buf = vec![0x81; depth];
+        buf.push(0x00);
+        buf
+    };
+    assert!(helpers::cbor_diag(&nested(helpers::MAX_DIAG_DEPTH - 1)).is_some());
+    assert_eq!(helpers::cbor_diag(&nested(helpers::MAX_DIAG_DEPTH)), None);
+    assert_eq!(helpers::cbor_diag(&nested(200_000)), None);
+
+    let mut bndl = bundle::new_std_payload_bundle(
+        EndpointID::with_ipn(1, 2).unwrap(),
+        EndpointID::with_ipn(2, 1).unwrap(),
+        b"ABC".to_vec(),
+    );
+    let diag = helpers::cbor_diag(&bndl.to_cbor()).unwrap();
+    assert!(diag.starts_with("[_ [7, "));
+    assert!(diag.ends_with("[1, 1, 0, 0, h'414243']]"));
+}
+
+#[test]
+fn bundle_stream_tests() {
+    let mut b1 = bundle::new_std_payload_bundle(
+        "dtn://node1/a".try_into().unwrap(),
+        "dtn://node2/inbox".try_into().unwrap(),
+        b"first".to_vec(),
+    );
+    let mut b2 = bundle::new_std_payload_bundle(
+        "ipn:3.1".try_into().unwrap(),
+        "ipn:4.2".try_into().unwrap(),
+        b"second bundle".to_vec(),
+    );
+    let (c1, c2) = (b1.to_cbor(), b2.to_cbor());
+    let mut buf = c1.clone();
+    buf.extend_from_slice(&c2);
+
+    let mut stream = bundle::BundleStream::new(&buf);
+    let (d1, raw1) = stream.next().unwrap().unwrap();
+    assert_eq!(d1, b1);
+    assert_eq!(raw1, &c1[..]);
+    assert_eq!(stream.offset(), c1.len());
+    let (d2, raw2) = stream.next().unwrap().unwrap();
+    assert_eq!(d2, b2);
+    assert_eq!(raw2, &c2[..]);
+    assert!(stream.next().is_none());
+
+    assert_eq!(bundle::BundleStream::new(&[]).count(), 0);
+
+    // truncated second bundle yields an error and stops
+    let truncated = &buf[..buf.len() - 3];
+    let results: Vec<_> = bundle::BundleStream::new(truncated).collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+/// Re-encode a bundle as a definite-length array with a non-minimally encoded
+/// primary block version, as another implementation might send it.
+fn foreign_encoding(b: &mut Bundle) -> Vec<u8> {
+    let encoded = b.to_cbor();
+    let report = Bundle::try_from(encoded.as_slice()).unwrap().crc_report();
+    let mut primary = encoded[report[0].span.clone().unwrap()].to_vec();
+    primary.splice(1..2, [0x18, 0x07]);
+    let crc = crc::crc_of_encoded(b.primary.crc_type(), &primary);
+    let len = primary.len();
+    primary[len - 2..].copy_from_slice(crc.bytes().unwrap());
+
+    let mut out = vec![0x80 + report.len() as u8];
+    out.extend(primary);
+    out.extend(&encoded[report[1].span.clone().unwrap().start..encoded.len() - 1]);
+    out
+}
+
+#[test]
+fn bundle_preserve_encoding_tests() {
+    let mut orig = new_complete_bundle(crc::CRC_16);
+    let foreign = foreign_encoding(&mut orig);
+
+    // regular decoding normalizes the encoding
+    let mut b = Bundle::try_from(foreign.as_slice()).unwrap();
+    assert!(b.validate().is_ok());
+    assert_ne!(b.to_cbor(), foreign);
+
+    let mut b = Bundle::from_cbor_preserving(&foreign).unwrap();
+    assert_eq!(b.to_cbor(), foreign);
+    // recalculating CRCs leaves unchanged blocks alone
+    b.calculate_crc();
+    assert_eq!(b.to_cbor(), foreign);
+    assert!(Bundle::from_cbor_preserving(&foreign[..foreign.len() - 1]).is_err());
+
+    // only changed blocks are re-encoded
+    let spans: Vec<_> = b
+        .crc_report()
+        .into_iter()
+        .map(|r| r.span.unwrap())
+        .collect();
+    assert!(b.update_extensions(
+        "dtn://node3/".try_into().unwrap(),
+        Duration::from_millis(10)
+    ));
+    let updated = b.to_cbor();
+    assert_eq!(updated[..spans[1].start], foreign[..spans[1].start]);
+    assert_eq!(
+        updated[updated.len() - spans[4].len()..],
+        foreign[spans[4].clone()]
+    );
+    let decoded = Bundle::try_from(updated.as_slice()).unwrap();
+    assert!(decoded.validate().is_ok());
+    assert_eq!(decoded.previous_node().unwrap().to_string(), "dtn://node3/");
+
+    // removing a block changes the array length, falling back to indefinite length
+    let mut b = Bundle::from_cbor_preserving(&foreign).unwrap();
+    b.remove_block(3).unwrap();
+    let stripped = b.to_cbor();
+    assert_eq!(stripped[0], 0x9f);
+    assert_eq!(stripped[1..spans[1].start], foreign[1..spans[1].start]);
+    assert!(
+        Bundle::try_from(stripped.as_slice())
+            .unwrap()
+            .validate()
+            .is_ok()
+    );
+
+    // direct changes to block fields are picked up as well
+    let mut b = Bundle::from_cbor_preserving(&foreign).unwrap();
+    b.primary.lifetime = Duration::from_secs(60);
+    let changed = b.to_cbor();
+    assert_ne!(changed[..spans[1].start], foreign[..spans[1].start]);
+    let decoded = Bundle::try_from(changed.as_slice()).unwrap();
+    assert!(decoded.validate().is_ok());
+    assert_eq!(decoded.primary.lifetime, Duration::from_secs(60));
+}
+
+#[test]
+fn bundle_block_mutation_tests() {
+    let mut b = new_empty_bundle(crc::CRC_16);
+    assert_eq!(b.replace_payload(b"ABC".to_vec()), None);
+    assert_eq!(b.block(1).unwrap().crc_type(), crc::CRC_16);
+
+    let hc = canonical::new_hop_count_block(23, BlockControlFlags::empty(), 16);
+    assert_eq!(b.insert_block(hc.clone()), Ok(2));
+    assert_eq!(
+        b.insert_block(hc),
+        Err(bundle::BlockError::DuplicateBlockType(
+            canonical::HOP_COUNT_BLOCK
+        ))
+    );
+    assert_eq!(
+        b.insert_block(canonical::new_payload_block(
+            BlockControlFlags::empty(),
+            vec![]
+        )),
+        Err(bundle::BlockError::DuplicateBlockType(
+            canonical::PAYLOAD_BLOCK
+        ))
+    );
+    let unknown =
+        canonical::new_canonical_block(192, 1, 0, canonical::CanonicalData::Unknown(vec![1, 2, 3]));
+    assert_eq!(b.insert_block(unknown.clone()), Ok(3));
+    assert_eq!(b.insert_block(unknown), Ok(4));
+    let numbers: Vec<u64> = b.canonicals.iter().map(|c| c.block_number).collect();
+    assert_eq!(numbers, vec![4, 3, 2, 1]);
+    b.calculate_crc();
+    assert!(b.validate().is_ok());
+
+    // add_block applies the bundle CRC type and keeps all checksums valid
+    let age = canonical::new_bundle_age_block(0, BlockControlFlags::empty(), 0);
+    assert_eq!(b.add_block(age.clone()), Ok(5));
+    assert_eq!(b.block(5).unwrap().crc_type(), crc::CRC_16);
+    assert!(b.crc_valid());
+    assert_eq!(
+        b.add_block(age),
+        Err(bundle::BlockError::DuplicateBlockType(
+            canonical::BUNDLE_AGE_BLOCK
+        ))
+    );
+    assert!(b.remove_block(5).is_some());
+    b.calculate_crc();
+
+    // changes through block_mut are covered by a fresh CRC
+    let encoded = b.to_cbor();
+    let mut b = Bundle::from_cbor_preserving(&encoded).unwrap();
+    b.block_mut(2)
+        .unwrap()
+        .set_data(canonical::CanonicalData::HopCount(16, 3));
+    assert!(b.block_mut(5).is_none());
+    let mut decoded = Bundle::try_from(b.to_cbor()).unwrap();
+    assert!(decoded.validate().is_ok());
+    assert_eq!(decoded.block(2).unwrap().hop_count_get(), Some((16, 3)));
+
+    assert_eq!(
+        decoded.replace_payload(b"DEF".to_vec()),
+        Some(b"ABC".to_vec())
+    );
+    assert_eq!(decoded.payload().unwrap(), b"DEF");
+    assert_eq!(decoded.canonicals.last().unwrap().block_number, 1);
+    let decoded = Bundle::try_from(decoded.to_cbor()).unwrap();
+    assert!(decoded.validate().is_ok());
+    assert!(decoded.block(0).is_none());
+}
+
+#[test]
+fn bundle_summary_tests() {
+    let mut b = new_complete_bundle(crc::CRC_32);
+    b.primary.bundle_control_flags = (BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED
+        | BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY)
+        .bits();
+    // without a creation time the age is taken from the bundle age block
+    b.primary.creation_timestamp = dtntime::CreationTimestamp::with_time_and_seq(0, 0);
+    b.calculate_crc();
+
+    let summary = b.summary();
+    assert_eq!(summary.id, b.id());
+    assert_eq!(summary.destination.to_string(), "dtn://node2/inbox");
+    assert_eq!(summary.hop_count, Some((16, 0)));
+    assert_eq!(summary.payload_len, 3);
+    assert_eq!(summary.administrative_record, None);
+    assert!(summary.invalid_crc_blocks.is_empty());
+    assert_eq!(summary.lifetime_status(), "1h left");
+    let names: Vec<Option<&str>> = summary.blocks.iter().map(|b| b.type_name).collect();
+    assert_eq!(
+        names,
+        vec![
+            Some("previous_node"),
+            Some("hop_count"),
+            Some("bundle_age"),
+            Some("payload")
+        ]
+    );
+    assert_eq!(summary.blocks[1].to_string(), "#3 hop_count (10, crc 2)");
+
+    let line = summary.to_string();
+    assert!(!line.contains('\n'));
+    assert_eq!(
+        line,
+        format!(
+            "{} -> dtn://node2/inbox, 3 bytes, 1h left, hops 0/16, \
+             flags BUNDLE_STATUS_REQUEST_DELIVERY | BUNDLE_MUST_NOT_FRAGMENTED, \
+             blocks [previous_node, hop_count, bundle_age, payload]",
+            b.id()
+        )
+    );
+
+    let table = format!("{:#}", summary);
+    assert!(table.starts_with(&format!("bundle:   {}\n", b.id())));
+    assert!(table.contains("\nhops:     0/16\n"));
+    assert!(table.contains("\npayload:  3 bytes\n"));
+    assert!(table.ends_with("\ncrc:      valid"));
+
+    b.primary.lifetime = Duration::from_secs(60 * 60 * 2);
+    let summary = b.summary();
+    assert_eq!(summary.invalid_crc_blocks, vec![0]);
+    assert!(summary.to_string().ends_with(", crc invalid (#0)"));
+
+    let flags = BlockControlFlags::BLOCK_REPLICATE | BlockControlFlags::BLOCK_REMOVE;
+    assert_eq!(flags.to_string(), "BLOCK_REPLICATE | BLOCK_REMOVE");
+    assert_eq!(BundleControlFlags::empty().to_string(), "");
+}
+
+#[test]
+fn bundle_summary_admin_record_tests() {
+    let mut orig = new_complete_bundle(crc::CRC_NO);
+    orig.primary.bundle_control_flags = BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY.bits();
+    let report = administrative_record::status_report_bundle(
+        &orig,
+        "dtn://node2/".try_into().unwrap(),
+        crc::CRC_NO,
+        &[administrative_record::DELIVERED_BUNDLE],
+        administrative_record::NO_INFORMATION,
+    )
+    .unwrap();
+
+    let summary = report.summary();
+    assert_eq!(
+        summary.administrative_record,
+        Some(format!(
+            "status report for {}: delivered (no_information)",
+            orig.id()
+        ))
+    );
+    assert!(
+        summary
+            .to_string()
+            .ends_with(summary.administrative_record.as_deref().unwrap())
+    );
+}
+
+fn assert_encoded_len(b: &Bundle) {
+    let mut encoded = b.clone();
+    assert_eq!(b.encoded_len(), encoded.to_cbor().len(), "{:?}", b);
+    assert_eq!(b.primary.encoded_len(), b.primary.to_cbor().len());
+    for cb in &b.canonicals {
+        assert_eq!(cb.encoded_len(), cb.to_cbor().len(), "{:?}", cb);
+    }
+}
+
+#[test]
+fn bundle_encoded_len_tests() {
+    for crc_type in [crc::CRC_NO, crc::CRC_16, crc::CRC_32] {
+        let b = new_complete_bundle(crc_type);
+        assert_encoded_len(&b);
+        assert_encoded_len(&new_empty_bundle(crc_type));
+
+        // CRCs not calculated yet count with their full size
+        let mut unset = b.clone();
+        unset.set_crc(crc_type);
+        assert_eq!(unset.encoded_len(), b.encoded_len());
+
+        // lengths crossing the CBOR head size boundaries
+        for len in [23, 24, 255, 256, 65535, 65536] {
+            let mut b = b.clone();
+            b.set_payload(vec![0x42; len]);
+            b.primary.destination = "ipn:4294967296.65536".try_into().unwrap();
+            b.primary.report_to = eid::EndpointID::none();
+            b.primary.source = eid::EndpointID::with_dtn(&"n".repeat(len)).unwrap();
+            b.primary.creation_timestamp =
+                dtntime::CreationTimestamp::with_time_and_seq(u64::MAX, len as u64);
+            b.primary.lifetime = Duration::from_millis(len as u64);
+            b.primary.bundle_control_flags = BundleControlFlags::BUNDLE_IS_FRAGMENT.bits();
+            b.primary.fragmentation_offset = len as u64;
+            b.primary.total_data_length = u64::from(u32::MAX);
+            b.add_canonical_block(canonical::new_canonical_block(
+                len as u64,
+                0,
+                BlockControlFlags::BLOCK_REMOVE.bits(),
+                canonical::CanonicalData::Unknown(vec![1; len]),
+            ));
+            b.extension_block_by_type_mut(canonical::BUNDLE_AGE_BLOCK)
+                .unwrap()
+                .set_data(canonical::CanonicalData::BundleAge(u64::MAX));
+            b.extension_block_by_type_mut(canonical::HOP_COUNT_BLOCK)
+                .unwrap()
+                .set_data(canonical::CanonicalData::HopCount(255, 24));
+            assert_encoded_len(&b);
+        }
+    }
+
+    // preserved encodings are counted as received
+    let mut orig = new_complete_bundle(crc::CRC_16);
+    let foreign = foreign_encoding(&mut orig);
+    let mut b = Bundle::from_cbor_preserving(&foreign).unwrap();
+    assert_eq!(b.encoded_len(), foreign.len());
+    assert_encoded_len(&Bundle::try_from(foreign.as_slice()).unwrap());
+    b.remove_block(3).unwrap();
+    assert_encoded_len(&b);
+}
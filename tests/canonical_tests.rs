@@ -1,3 +1,4 @@
+use bp7::crc::CrcBlock;
 use bp7::flags::*;
 use bp7::*;
 use std::convert::TryFrom;
@@ -144,3 +145,130 @@ fn bundleage_tests() {
     assert_eq!(wrong_block.bundle_age_get(), None);
     assert!(!wrong_block.bundle_age_update(2342));
 }
+
+#[test]
+fn bundleage_duration_tests() {
+    let mut block = new_bundle_age_block(1, BlockControlFlags::empty(), 1500);
+    assert_eq!(
+        block.bundle_age(),
+        Some(std::time::Duration::from_millis(1500))
+    );
+
+    assert!(block.set_bundle_age(std::time::Duration::from_micros(2_500_900)));
+    assert_eq!(block.bundle_age_get(), Some(2500));
+
+    let mut wrong_block = new_hop_count_block(1, BlockControlFlags::empty(), 1);
+    assert_eq!(wrong_block.bundle_age(), None);
+    assert!(!wrong_block.set_bundle_age(std::time::Duration::from_secs(1)));
+}
+
+#[test]
+fn typed_extension_block_tests() {
+    assert!(matches!(
+        HopCountBlock::new(0),
+        Err(CanonicalBuilderError::InvalidHopLimit)
+    ));
+
+    let hc: CanonicalBlock = HopCountBlock::new(16)
+        .unwrap()
+        .count(2)
+        .flags(BlockControlFlags::BLOCK_REPLICATE)
+        .crc(bp7::crc::CRC_32)
+        .into();
+    assert_eq!(hc.block_type, HOP_COUNT_BLOCK);
+    assert_eq!(hc.hop_count_get(), Some((16, 2)));
+    assert_eq!(
+        hc.block_control_flags,
+        BlockControlFlags::BLOCK_REPLICATE.bits()
+    );
+    assert_eq!(hc.crc_type(), bp7::crc::CRC_32);
+
+    let age: CanonicalBlock = BundleAgeBlock::new(std::time::Duration::from_secs(3)).into();
+    assert_eq!(age.block_type, BUNDLE_AGE_BLOCK);
+    assert_eq!(age.bundle_age_get(), Some(3000));
+    assert_eq!(age.crc_type(), bp7::crc::CRC_NO);
+
+    let prev: CanonicalBlock = PreviousNodeBlock::new("dtn://node1/".try_into().unwrap()).into();
+    assert_eq!(prev.block_type, PREVIOUS_NODE_BLOCK);
+    assert_eq!(
+        prev.previous_node_get(),
+        Some(&EndpointID::try_from("dtn://node1/").unwrap())
+    );
+
+    let mut bndl = bundle::Bundle::default();
+    bndl.insert_block(new_payload_block(
+        BlockControlFlags::empty(),
+        b"ABC".to_vec(),
+    ))
+    .unwrap();
+    assert_eq!(bndl.insert_block(hc).unwrap(), 2);
+    assert_eq!(bndl.insert_block(age).unwrap(), 3);
+    assert_eq!(bndl.insert_block(prev).unwrap(), 4);
+    bndl.calculate_crc();
+    encode_decode_test_canonical(
+        bndl.extension_block_by_type(HOP_COUNT_BLOCK)
+            .unwrap()
+            .clone(),
+    );
+}
+
+#[test]
+fn canonical_builder_validation_tests() {
+    let mismatch = CanonicalBlockBuilder::new()
+        .block_type(PAYLOAD_BLOCK)
+        .block_number(1)
+        .data(CanonicalData::HopCount(16, 0))
+        .build();
+    assert_eq!(
+        mismatch.unwrap_err(),
+        CanonicalBuilderError::DataMismatch(PAYLOAD_BLOCK)
+    );
+
+    let mismatch = CanonicalBlockBuilder::new()
+        .block_type(192)
+        .block_number(2)
+        .data(CanonicalData::BundleAge(0))
+        .build();
+    assert_eq!(
+        mismatch.unwrap_err(),
+        CanonicalBuilderError::DataMismatch(192)
+    );
+
+    let payload = CanonicalBlockBuilder::new()
+        .block_type(PAYLOAD_BLOCK)
+        .block_number(5)
+        .data(CanonicalData::Data(b"ABC".to_vec()))
+        .build();
+    assert_eq!(
+        payload.unwrap_err(),
+        CanonicalBuilderError::PayloadBlockNumber(5)
+    );
+
+    for data in [
+        CanonicalData::HopCount(0, 0),
+        CanonicalData::HopCount(16, 17),
+    ] {
+        let hop_count = CanonicalBlockBuilder::new()
+            .block_type(HOP_COUNT_BLOCK)
+            .block_number(2)
+            .data(data)
+            .build();
+        assert_eq!(
+            hop_count.unwrap_err(),
+            CanonicalBuilderError::InvalidHopLimit
+        );
+    }
+    CanonicalBlockBuilder::new()
+        .block_type(HOP_COUNT_BLOCK)
+        .block_number(2)
+        .data(CanonicalData::HopCount(16, 16))
+        .build()
+        .unwrap();
+
+    CanonicalBlockBuilder::new()
+        .block_type(192)
+        .block_number(2)
+        .data(CanonicalData::Unknown(vec![1, 2, 3]))
+        .build()
+        .unwrap();
+}
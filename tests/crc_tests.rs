@@ -1,5 +1,6 @@
 use bp7::crc::CrcBlock;
 use bp7::*;
+use std::convert::TryFrom;
 #[test]
 fn crc_valid_tests() {
     let mut b = helpers::rnd_bundle(dtntime::CreationTimestamp::now());
@@ -16,6 +17,16 @@ fn crc_valid_tests() {
     assert!(b.crc_valid());
 }
 
+#[test]
+fn crc_type_validation_tests() {
+    assert_eq!(crc::validate_crc_type(crc::CRC_NO), Ok(crc::CRC_NO));
+    assert_eq!(crc::validate_crc_type(crc::CRC_32), Ok(crc::CRC_32));
+    assert_eq!(
+        crc::validate_crc_type(3),
+        Err(crc::CrcError::UnknownCrcType(3))
+    );
+}
+
 #[test]
 fn crc_invalid_tests() {
     let mut b = helpers::rnd_bundle(dtntime::CreationTimestamp::now());
@@ -30,3 +41,171 @@ fn crc_invalid_tests() {
     b.primary.set_crc(crc::CrcValue::Crc32([23, 42, 23, 42]));
     assert!(!b.crc_valid());
 }
+
+#[test]
+fn crc_encode_tests() {
+    for crc_type in [crc::CRC_NO, crc::CRC_16, crc::CRC_32] {
+        let mut b = helpers::rnd_bundle(dtntime::CreationTimestamp::now());
+        b.set_crc(crc_type);
+        let encoded = b.to_cbor();
+        // CRCs are filled in while encoding
+        assert_eq!(b.primary.crc_type(), crc_type);
+        assert_eq!(crc::calculate_crc(&b.primary), *b.primary.crc_value());
+        assert!(b.crc_valid());
+
+        let mut decoded = Bundle::try_from(encoded.as_slice()).unwrap();
+        assert_eq!(decoded, b);
+        assert!(decoded.crc_valid());
+        assert_eq!(decoded.to_cbor(), encoded);
+    }
+}
+
+#[test]
+fn crc_decode_tests() {
+    let mut b = helpers::rnd_bundle(dtntime::CreationTimestamp::now());
+    b.set_crc(crc::CRC_32);
+    let mut encoded = b.to_cbor();
+    let last = encoded.len() - 2;
+
+    // corrupt the CRC of the last block
+    encoded[last] ^= 0xff;
+    let mut decoded = Bundle::try_from(encoded.as_slice()).unwrap();
+    assert!(!decoded.crc_valid());
+    assert!(decoded.validate().is_err());
+
+    // recalculating the CRC replaces the received one
+    decoded.calculate_crc();
+    assert!(decoded.crc_valid());
+
+    // fields changed after decoding are not covered by the received checksums
+    encoded[last] ^= 0xff;
+    let mut decoded = Bundle::try_from(encoded.as_slice()).unwrap();
+    assert!(decoded.crc_valid());
+    decoded.primary.lifetime += std::time::Duration::from_secs(1);
+    assert!(!decoded.crc_valid());
+    assert!(decoded.validate().is_err());
+    assert_eq!(decoded.summary().invalid_crc_blocks, vec![0]);
+    let report = decoded.crc_report();
+    assert_eq!(report[0].status, crc::CrcStatus::Invalid);
+    assert!(report[1..].iter().all(|r| r.is_valid()));
+
+    let mut decoded = Bundle::try_from(encoded.as_slice()).unwrap();
+    decoded.canonicals[0].block_control_flags ^= 0x01;
+    let changed = decoded.canonicals[0].block_number;
+    assert!(!decoded.crc_valid());
+    assert_eq!(decoded.summary().invalid_crc_blocks, vec![changed]);
+    decoded.calculate_crc();
+    assert!(decoded.crc_valid());
+    encoded[last] ^= 0xff;
+
+    assert!(Bundle::try_from(&encoded[..encoded.len() - 1]).is_err());
+    encoded.push(0x00);
+    assert!(Bundle::try_from(encoded.as_slice()).is_err());
+}
+
+#[test]
+fn crc_report_tests() {
+    let mut b = helpers::rnd_bundle(dtntime::CreationTimestamp::now());
+    b.set_crc(crc::CRC_16);
+    let encoded = b.to_cbor();
+
+    // not decoded, no spans
+    let report = b.crc_report();
+    assert_eq!(report.len(), b.canonicals.len() + 1);
+    assert!(report.iter().all(|r| r.span.is_none() && r.is_valid()));
+
+    let decoded = Bundle::try_from(encoded.as_slice()).unwrap();
+    let report = decoded.crc_report();
+    assert_eq!(report[0].block_number, 0);
+    assert_eq!(report[0].block_type, None);
+    assert_eq!(
+        report[0].span,
+        Some(1..report[1].span.clone().unwrap().start)
+    );
+    assert_eq!(
+        report.last().unwrap().span.clone().unwrap().end,
+        encoded.len() - 1
+    );
+    assert!(
+        report
+            .iter()
+            .all(|r| r.status == crc::CrcStatus::Valid && r.crc_type == crc::CRC_16)
+    );
+
+    // re-encode the version of the primary block with a non-minimal integer width
+    let span = report[0].span.clone().unwrap();
+    let mut primary = encoded[span.clone()].to_vec();
+    assert_eq!(primary[1], 0x07);
+    primary.splice(1..2, [0x18, 0x07]);
+    let crc = crc::crc_of_encoded(crc::CRC_16, &primary);
+    let len = primary.len();
+    primary[len - 2..].copy_from_slice(crc.bytes().unwrap());
+    let mut reencoded = encoded.clone();
+    reencoded.splice(span, primary);
+
+    let decoded = Bundle::try_from(reencoded.as_slice()).unwrap();
+    assert_eq!(decoded.id(), b.id());
+    assert_eq!(decoded.crc_report()[0].status, crc::CrcStatus::Valid);
+    assert!(decoded.validate().is_ok());
+    // re-encoding the block yields a different checksum
+    assert_ne!(crc::calculate_crc(&decoded.primary), decoded.primary.crc);
+
+    // corrupt the data of the last block, right before its CRC
+    let mut corrupted = encoded;
+    let pos = report.last().unwrap().span.clone().unwrap().end - 4;
+    corrupted[pos] ^= 0x01;
+    let report = Bundle::try_from(corrupted.as_slice()).unwrap().crc_report();
+    assert_eq!(report.last().unwrap().status, crc::CrcStatus::Invalid);
+    assert!(report[..report.len() - 1].iter().all(|r| r.is_valid()));
+
+    let mut b = helpers::rnd_bundle(dtntime::CreationTimestamp::now());
+    b.primary.set_crc_type(7);
+    assert_eq!(b.crc_report()[0].status, crc::CrcStatus::Unsupported(7));
+    assert!(!b.crc_valid());
+}
+
+#[test]
+fn crc_unsupported_type_tests() {
+    let mut b = helpers::rnd_bundle(dtntime::CreationTimestamp::now());
+    b.set_crc(crc::CRC_16);
+    let mut encoded = b.to_cbor();
+    let span = Bundle::try_from(encoded.as_slice()).unwrap().crc_report()[1..]
+        .last()
+        .unwrap()
+        .span
+        .clone()
+        .unwrap();
+    // block array header, type, number and flags precede the CRC type,
+    // drop the CRC value as its length is unknown for other types
+    assert_eq!(encoded[span.start], 0x86);
+    assert_eq!(encoded[span.start + 4], crc::CRC_16);
+    encoded[span.start] = 0x85;
+    encoded[span.start + 4] = 3;
+    encoded.drain(span.end - 3..span.end);
+    let span = span.start..span.end - 3;
+
+    let decoded = Bundle::try_from(encoded.as_slice()).unwrap();
+    let report = decoded.crc_report();
+    assert_eq!(
+        report.last().unwrap().status,
+        crc::CrcStatus::Unsupported(3)
+    );
+    let errors = decoded.validate().unwrap_err();
+    assert!(
+        errors
+            .iter()
+            .any(|e| e.to_string().contains("unsupported CRC type 3"))
+    );
+    assert!(
+        !errors
+            .iter()
+            .any(|e| e.to_string().contains("CRC check failed"))
+    );
+
+    let raw = &encoded[span.clone()];
+    let blanked = crc::blank_crc(crc::CRC_16, raw);
+    assert_eq!(blanked.len(), raw.len());
+    assert_eq!(blanked[..raw.len() - 2], raw[..raw.len() - 2]);
+    assert_eq!(blanked[raw.len() - 2..], [0, 0]);
+    assert_eq!(crc::blank_crc(3, raw), raw);
+}
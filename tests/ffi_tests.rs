@@ -0,0 +1,246 @@
+use bp7::administrative_record::*;
+use bp7::ffi::*;
+use bp7::flags::BundleControlFlags;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+fn last_error_message() -> String {
+    unsafe {
+        let msg = bp7_last_error_message();
+        let s = CStr::from_ptr(msg).to_str().unwrap().to_owned();
+        bp7_string_free(msg);
+        s
+    }
+}
+
+fn take_string(s: *mut c_char) -> String {
+    assert!(!s.is_null());
+    unsafe {
+        let out = CStr::from_ptr(s).to_str().unwrap().to_owned();
+        bp7_string_free(s);
+        out
+    }
+}
+
+fn payload_buffer(data: &mut [u8]) -> Buffer {
+    Buffer {
+        data: data.as_mut_ptr(),
+        len: data.len() as u32,
+    }
+}
+
+#[test]
+fn ffi_error_code_tests() {
+    unsafe {
+        let mut data = b"ABC".to_vec();
+        let mut payload = payload_buffer(&mut data);
+        let src = CString::new("dtn://node1/").unwrap();
+        let dst = CString::new("not an endpoint").unwrap();
+
+        let bndl = bundle_new_default(src.as_ptr(), dst.as_ptr(), 1000, &mut payload);
+        assert!(bndl.is_null());
+        assert_eq!(bp7_last_error_code(), Bp7ErrorCode::InvalidEndpoint);
+        assert!(last_error_message().contains("destination"));
+
+        let mut garbage = vec![0xff, 0x00, 0x01];
+        let mut buf = payload_buffer(&mut garbage);
+        assert!(bundle_from_cbor(&mut buf).is_null());
+        assert_eq!(bp7_last_error_code(), Bp7ErrorCode::DecodeFailed);
+
+        assert_eq!(
+            bundle_validate(std::ptr::null_mut()),
+            Bp7ErrorCode::NullPointer
+        );
+
+        let dst = CString::new("dtn://node2/inbox").unwrap();
+        let bndl = bundle_new_default(src.as_ptr(), dst.as_ptr(), 1000, &mut payload);
+        assert!(!bndl.is_null());
+        assert_eq!(bp7_last_error_code(), Bp7ErrorCode::Ok);
+        assert_eq!(last_error_message(), "");
+
+        assert_eq!(bundle_set_crc(bndl, 7), Bp7ErrorCode::InvalidArgument);
+        assert_eq!(
+            bundle_add_hop_count_block(bndl, 0),
+            Bp7ErrorCode::InvalidArgument
+        );
+        let (mut limit, mut count) = (0, 0);
+        assert_eq!(
+            bundle_hop_count(bndl, &mut limit, &mut count),
+            Bp7ErrorCode::MissingBlock
+        );
+        bundle_free(bndl);
+    }
+}
+
+#[test]
+fn ffi_builder_tests() {
+    unsafe {
+        let builder = primary_builder_new();
+        let dst = CString::new("dtn://node2/inbox").unwrap();
+        let src = CString::new("dtn://node1/app").unwrap();
+        let invalid = CString::new("foo:bar").unwrap();
+
+        assert_eq!(
+            primary_builder_destination(builder, dst.as_ptr()),
+            Bp7ErrorCode::Ok
+        );
+        assert_eq!(
+            primary_builder_source(builder, invalid.as_ptr()),
+            Bp7ErrorCode::InvalidEndpoint
+        );
+        assert_eq!(
+            primary_builder_source(builder, src.as_ptr()),
+            Bp7ErrorCode::Ok
+        );
+        assert_eq!(
+            primary_builder_report_to(builder, src.as_ptr()),
+            Bp7ErrorCode::Ok
+        );
+        assert_eq!(
+            primary_builder_creation_timestamp(builder, 0, 7),
+            Bp7ErrorCode::Ok
+        );
+        assert_eq!(primary_builder_lifetime(builder, 60_000), Bp7ErrorCode::Ok);
+        assert_eq!(
+            primary_builder_flags(
+                builder,
+                BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED.bits()
+            ),
+            Bp7ErrorCode::Ok
+        );
+
+        let mut data = b"hello".to_vec();
+        let mut payload = payload_buffer(&mut data);
+        let bndl = bundle_new(builder, &mut payload, bp7::crc::CRC_32);
+        primary_builder_free(builder);
+        assert!(!bndl.is_null());
+        assert_eq!(take_string(bundle_id(bndl)), "dtn://node1/app-0-7");
+
+        assert_eq!(bundle_add_hop_count_block(bndl, 16), Bp7ErrorCode::Ok);
+        assert_eq!(
+            bundle_add_hop_count_block(bndl, 16),
+            Bp7ErrorCode::DuplicateBlock
+        );
+        assert_eq!(bundle_add_bundle_age_block(bndl, 10), Bp7ErrorCode::Ok);
+        assert_eq!(
+            bundle_add_previous_node_block(bndl, src.as_ptr()),
+            Bp7ErrorCode::Ok
+        );
+        assert_eq!(bundle_validate(bndl), Bp7ErrorCode::Ok);
+
+        let node = CString::new("dtn://node3/").unwrap();
+        assert_eq!(
+            bundle_update_extensions(bndl, node.as_ptr(), 5),
+            Bp7ErrorCode::Ok
+        );
+
+        let cbor = bundle_to_cbor(bndl);
+        bundle_free(bndl);
+        let decoded = bundle_from_cbor(cbor);
+        buffer_free(cbor);
+        assert!(!decoded.is_null());
+
+        let (mut limit, mut count) = (0, 0);
+        assert_eq!(
+            bundle_hop_count(decoded, &mut limit, &mut count),
+            Bp7ErrorCode::Ok
+        );
+        assert_eq!((limit, count), (16, 1));
+        let mut age = 0;
+        assert_eq!(bundle_age(decoded, &mut age), Bp7ErrorCode::Ok);
+        assert_eq!(age, 15);
+        assert_eq!(take_string(bundle_previous_node(decoded)), "dtn://node3/");
+
+        let payload = bundle_payload(decoded);
+        assert_eq!(
+            std::slice::from_raw_parts((*payload).data, (*payload).len as usize),
+            b"hello"
+        );
+        buffer_free(payload);
+        bundle_free(decoded);
+    }
+}
+
+#[test]
+fn ffi_status_report_tests() {
+    unsafe {
+        let builder = primary_builder_new();
+        let dst = CString::new("dtn://node2/inbox").unwrap();
+        let src = CString::new("dtn://node1/app").unwrap();
+        let reporter = CString::new("dtn://node2/").unwrap();
+        primary_builder_destination(builder, dst.as_ptr());
+        primary_builder_source(builder, src.as_ptr());
+        primary_builder_report_to(builder, src.as_ptr());
+        primary_builder_creation_timestamp(builder, 2000, 3);
+        primary_builder_flags(
+            builder,
+            BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY.bits(),
+        );
+        let mut data = b"hello".to_vec();
+        let mut payload = payload_buffer(&mut data);
+        let orig = bundle_new(builder, &mut payload, bp7::crc::CRC_NO);
+        primary_builder_free(builder);
+        assert!(!orig.is_null());
+
+        assert!(bundle_status_report(orig).is_null());
+        assert_eq!(bp7_last_error_code(), Bp7ErrorCode::NotAdministrativeRecord);
+
+        let received = 1 << RECEIVED_BUNDLE;
+        let delivered = 1 << DELIVERED_BUNDLE;
+        let report = bundle_new_status_report(
+            orig,
+            reporter.as_ptr(),
+            bp7::crc::CRC_16,
+            received,
+            NO_INFORMATION,
+            false,
+        );
+        assert!(report.is_null());
+        assert_eq!(
+            bp7_last_error_code(),
+            Bp7ErrorCode::StatusReportNotPermitted
+        );
+
+        let report = bundle_new_status_report(
+            orig,
+            reporter.as_ptr(),
+            bp7::crc::CRC_16,
+            received | delivered,
+            NO_INFORMATION,
+            false,
+        );
+        assert!(!report.is_null());
+        let info = bundle_status_report(report);
+        assert!(!info.is_null());
+        assert_eq!((*info).asserted, delivered);
+        assert_eq!((*info).reason, NO_INFORMATION);
+        assert_eq!((*info).timestamp, 2000);
+        assert_eq!((*info).seqno, 3);
+        assert_eq!(
+            CStr::from_ptr((*info).source).to_str().unwrap(),
+            "dtn://node1/app"
+        );
+        assert_eq!(
+            CStr::from_ptr((*info).refbundle).to_str().unwrap(),
+            "dtn://node1/app-2000-3"
+        );
+        status_report_info_free(info);
+        bundle_free(report);
+
+        let forced = bundle_new_status_report(
+            orig,
+            reporter.as_ptr(),
+            bp7::crc::CRC_NO,
+            received,
+            DEPLETED_STORAGE,
+            true,
+        );
+        assert!(!forced.is_null());
+        let info = bundle_status_report(forced);
+        assert_eq!((*info).asserted, received);
+        assert_eq!((*info).reason, DEPLETED_STORAGE);
+        status_report_info_free(info);
+        bundle_free(forced);
+        bundle_free(orig);
+    }
+}
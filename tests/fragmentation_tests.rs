@@ -0,0 +1,195 @@
+use bp7::flags::*;
+use bp7::fragmentation::*;
+use bp7::*;
+use std::convert::TryInto;
+
+fn new_bundle(payload: &[u8]) -> Bundle {
+    let mut b = bundle::new_std_payload_bundle(
+        "dtn://node1/src".try_into().unwrap(),
+        "dtn://node2/dst".try_into().unwrap(),
+        payload.to_vec(),
+    );
+    b.primary.bundle_control_flags = 0;
+    b.add_canonical_block(new_previous_node_block(
+        0,
+        BlockControlFlags::BLOCK_REPLICATE,
+        "dtn://node0/".try_into().unwrap(),
+    ));
+    b.set_crc(crc::CRC_32);
+    b.calculate_crc();
+    b
+}
+
+#[test]
+fn fragment_tests() {
+    let b = new_bundle(b"0123456789");
+    let frags = fragment(&b, 4).unwrap();
+    assert_eq!(frags.len(), 3);
+
+    let offsets: Vec<u64> = frags
+        .iter()
+        .map(|f| f.primary.fragmentation_offset)
+        .collect();
+    assert_eq!(offsets, vec![0, 4, 8]);
+    for f in frags.iter() {
+        assert!(f.primary.has_fragmentation());
+        assert_eq!(f.primary.total_data_length, 10);
+        assert!(f.clone().crc_valid());
+        assert!(f.validate().is_ok());
+        // replicated in every fragment
+        assert!(f.extension_block_by_type(PREVIOUS_NODE_BLOCK).is_some());
+    }
+    // hop count block is not replicated
+    assert!(frags[0].extension_block_by_type(HOP_COUNT_BLOCK).is_some());
+    assert!(frags[1].extension_block_by_type(HOP_COUNT_BLOCK).is_none());
+    assert_eq!(frags[2].payload().unwrap(), b"89");
+
+    // refragmenting keeps offsets relative to the original payload
+    let refrags = fragment(&frags[1], 3).unwrap();
+    assert_eq!(refrags[1].primary.fragmentation_offset, 7);
+    assert_eq!(refrags[1].primary.total_data_length, 10);
+
+    assert_eq!(fragment(&b, 10).unwrap(), vec![b.clone()]);
+    assert_eq!(fragment(&b, 0), Err(FragmentationError::InvalidSize));
+
+    let mut nofrag = b.clone();
+    nofrag.primary.bundle_control_flags = BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED.bits();
+    assert_eq!(
+        fragment(&nofrag, 4),
+        Err(FragmentationError::MustNotFragment)
+    );
+}
+
+#[test]
+fn reassemble_tests() {
+    let b = new_bundle(b"0123456789");
+    let mut frags = fragment(&b, 4).unwrap();
+    frags.reverse();
+    assert_eq!(reassemble(&frags).unwrap(), b);
+
+    // overlapping fragments
+    let mut overlapping = fragment(&b, 3).unwrap();
+    overlapping.extend(fragment(&b, 4).unwrap());
+    assert_eq!(reassemble(&overlapping).unwrap(), b);
+
+    assert_eq!(
+        reassemble(&frags[..2]),
+        Err(FragmentationError::Incomplete(0))
+    );
+    assert_eq!(
+        reassemble(&frags[1..]),
+        Err(FragmentationError::Incomplete(8))
+    );
+    assert_eq!(reassemble(&[]), Err(FragmentationError::NoFragments));
+    assert!(matches!(
+        reassemble(std::slice::from_ref(&b)),
+        Err(FragmentationError::NotAFragment(_))
+    ));
+
+    let other = fragment(&new_bundle(b"abcdefghij"), 4).unwrap();
+    let mut mixed = frags.clone();
+    mixed.push(other[0].clone());
+    mixed[3].primary.creation_timestamp = dtntime::CreationTimestamp::with_time_and_seq(1, 1);
+    assert!(matches!(
+        reassemble(&mixed),
+        Err(FragmentationError::Mismatch(_))
+    ));
+
+    // hostile total length and offset taken from received fragments
+    let mut huge = frags.clone();
+    for f in huge.iter_mut() {
+        f.primary.total_data_length = u64::MAX;
+    }
+    assert_eq!(reassemble(&huge), Err(FragmentationError::Incomplete(10)));
+    let mut overflow = frags.clone();
+    for f in overflow.iter_mut() {
+        f.primary.total_data_length = u64::MAX;
+    }
+    overflow[0].primary.fragmentation_offset = u64::MAX - 1;
+    assert!(matches!(
+        reassemble(&overflow),
+        Err(FragmentationError::OutOfBounds(_))
+    ));
+
+    // fragments of an empty original bundle do not exist
+    let mut empty = frags[..1].to_vec();
+    empty[0].primary.total_data_length = 0;
+    empty[0].primary.fragmentation_offset = 0;
+    empty[0].set_payload(Vec::new());
+    assert!(matches!(
+        reassemble(&empty),
+        Err(FragmentationError::ZeroTotalLength(_))
+    ));
+}
+
+#[test]
+fn remove_block_tests() {
+    let mut b = new_bundle(b"ABC");
+    let pn = b
+        .extension_block_by_type(PREVIOUS_NODE_BLOCK)
+        .unwrap()
+        .block_number;
+    assert_eq!(b.remove_block(pn).unwrap().block_type, PREVIOUS_NODE_BLOCK);
+    assert!(b.remove_block(pn).is_none());
+    assert!(b.remove_block(1).is_none());
+    assert!(b.payload().is_some());
+}
+
+/// Encoded size of the fragment of `b` carrying `data` at `offset`.
+fn fragment_size(b: &Bundle, offset: usize, data: &[u8]) -> usize {
+    let chunk = if offset == 0 {
+        b.payload().unwrap().len() - 1
+    } else {
+        offset
+    };
+    let frags = fragment(b, chunk).unwrap();
+    let mut frag = frags[usize::from(offset > 0)].clone();
+    assert_eq!(frag.primary.fragmentation_offset, offset as u64);
+    frag.set_payload(data.to_vec());
+    frag.to_cbor().len()
+}
+
+#[test]
+fn fitting_payload_tests() {
+    let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+    let b = new_bundle(&data);
+
+    for offset in [0, 300, 500, 999] {
+        for max_len in 40..400 {
+            match fitting_payload(&b, offset, max_len) {
+                Ok(slice) => {
+                    let end = offset + slice.len();
+                    assert_eq!(slice, &data[offset..end]);
+                    assert!(fragment_size(&b, offset, slice) <= max_len);
+                    if end < data.len() {
+                        assert!(fragment_size(&b, offset, &data[offset..end + 1]) > max_len);
+                    }
+                }
+                Err(err) => {
+                    assert_eq!(err, FragmentationError::TooSmall(max_len));
+                    assert!(fragment_size(&b, offset, &data[offset..offset + 1]) > max_len);
+                }
+            }
+        }
+    }
+
+    // later fragments only carry replicated blocks and leave more room for payload
+    let first = fitting_payload(&b, 0, 150).unwrap().len();
+    assert!(fitting_payload(&b, 500, 150).unwrap().len() > first);
+
+    let mut whole = b.clone();
+    assert_eq!(
+        fitting_payload(&b, 0, whole.to_cbor().len()).unwrap(),
+        &data[..]
+    );
+    assert_eq!(
+        fitting_payload(&b, 1000, 2000),
+        Err(FragmentationError::InvalidOffset(1000))
+    );
+    let mut must_not = b.clone();
+    must_not.primary.bundle_control_flags = BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED.bits();
+    assert_eq!(
+        fitting_payload(&must_not, 0, 500),
+        Err(FragmentationError::MustNotFragment)
+    );
+}
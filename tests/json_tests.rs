@@ -0,0 +1,220 @@
+use bp7::crc::{CRC_16, CRC_32, CRC_NO, CrcRawType};
+use bp7::flags::*;
+use bp7::json::{JSON_SCHEMA, JsonBundle};
+use bp7::*;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+fn new_json_bundle(crc_type: CrcRawType) -> Bundle {
+    let src: EndpointID = "dtn://node1/123456".try_into().unwrap();
+    let pblock = primary::PrimaryBlockBuilder::new()
+        .bundle_control_flags(
+            (BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED
+                | BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY)
+                .bits(),
+        )
+        .destination("ipn:2.1".try_into().unwrap())
+        .source(src.clone())
+        .report_to(src)
+        .creation_timestamp(CreationTimestamp::with_time_and_seq(
+            dtntime::dtn_time_now(),
+            3,
+        ))
+        .lifetime(Duration::from_secs(60 * 60))
+        .build()
+        .unwrap();
+    let mut b = Bundle::new(pblock, vec![]);
+    b.insert_block(new_payload_block(
+        BlockControlFlags::BLOCK_REPLICATE,
+        b"ABC\x00\xff".to_vec(),
+    ))
+    .unwrap();
+    b.insert_block(HopCountBlock::new(16).unwrap().count(2).into())
+        .unwrap();
+    b.insert_block(BundleAgeBlock::new(Duration::from_millis(1234)).into())
+        .unwrap();
+    b.insert_block(PreviousNodeBlock::new("dtn://node23/".try_into().unwrap()).into())
+        .unwrap();
+    b.insert_block(new_canonical_block(
+        192,
+        0,
+        BlockControlFlags::BLOCK_REMOVE.bits(),
+        CanonicalData::Unknown(vec![1, 2, 3]),
+    ))
+    .unwrap();
+    b.set_crc(crc_type);
+    b
+}
+
+#[test]
+fn json_roundtrip_tests() {
+    for crc_type in [CRC_NO, CRC_16, CRC_32] {
+        let mut bndl = new_json_bundle(crc_type);
+        let json = bndl.to_json();
+        let decoded = Bundle::try_from(json.clone()).unwrap();
+        assert_eq!(decoded, bndl);
+        assert_eq!(decoded.primary.crc, bndl.primary.crc);
+        decoded.validate().unwrap();
+        assert_eq!(decoded.clone().to_json(), json);
+    }
+
+    let mut fragment = new_json_bundle(CRC_NO);
+    fragment.primary.bundle_control_flags = BundleControlFlags::BUNDLE_IS_FRAGMENT.bits();
+    fragment.primary.fragmentation_offset = 2;
+    fragment.primary.total_data_length = 10;
+    let decoded = Bundle::try_from(fragment.to_json()).unwrap();
+    assert_eq!(decoded, fragment);
+}
+
+#[test]
+fn json_format_tests() {
+    let mut bndl = new_json_bundle(CRC_16);
+    let value: serde_json::Value = serde_json::from_str(&bndl.to_json()).unwrap();
+
+    assert_eq!(value["id"], bndl.id());
+    let primary = &value["primary"];
+    assert_eq!(primary["destination"], "ipn:2.1");
+    assert_eq!(primary["source"], "dtn://node1/123456");
+    assert_eq!(
+        primary["flags"],
+        serde_json::json!([
+            "BUNDLE_STATUS_REQUEST_DELIVERY",
+            "BUNDLE_MUST_NOT_FRAGMENTED"
+        ])
+    );
+    assert_eq!(primary["crc"]["type"], "crc16");
+    assert_eq!(primary["crc"]["value"].as_str().unwrap().len(), 4);
+    assert!(
+        primary["creation_timestamp"]["time"]
+            .as_str()
+            .unwrap()
+            .ends_with('Z')
+    );
+    assert_eq!(primary["creation_timestamp"]["seqno"], 3);
+    assert_eq!(primary["lifetime_ms"], 3_600_000);
+    assert!(primary.get("fragment").is_none());
+
+    let blocks = value["blocks"].as_array().unwrap();
+    let block = |name: &str| {
+        blocks
+            .iter()
+            .find(|b| b["type_name"] == name)
+            .unwrap_or_else(|| panic!("missing {} block", name))
+    };
+    assert_eq!(block("payload")["data"], "QUJDAP8=");
+    assert_eq!(
+        block("payload")["flags"],
+        serde_json::json!(["BLOCK_REPLICATE"])
+    );
+    assert_eq!(
+        block("hop_count")["data"],
+        serde_json::json!({"limit": 16, "count": 2})
+    );
+    assert_eq!(
+        block("bundle_age")["data"],
+        serde_json::json!({"age_ms": 1234})
+    );
+    assert_eq!(block("previous_node")["data"], "dtn://node23/");
+    let unknown = blocks.iter().find(|b| b["type"] == 192).unwrap();
+    assert!(unknown.get("type_name").is_none());
+    assert_eq!(unknown["data"], "AQID");
+
+    let epoch = CreationTimestamp::with_time_and_seq(0, 0);
+    bndl.primary.creation_timestamp = epoch;
+    let value: serde_json::Value = serde_json::from_str(&bndl.to_json()).unwrap();
+    assert_eq!(
+        value["primary"]["creation_timestamp"]["time"],
+        "2000-01-01T00:00:00.000Z"
+    );
+
+    // times beyond the year 9999 fall back to DTN time in milliseconds
+    let last = (253_402_300_800 - dtntime::SECONDS1970_TO2K) * 1000 - 1;
+    for (time, expected) in [
+        (last, "9999-12-31T23:59:59.999Z".to_string()),
+        (last + 1, (last + 1).to_string()),
+        (u64::MAX, u64::MAX.to_string()),
+    ] {
+        bndl.primary.creation_timestamp = CreationTimestamp::with_time_and_seq(time, 0);
+        let json = bndl.to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["primary"]["creation_timestamp"]["time"], expected);
+        let parsed = Bundle::try_from(json).unwrap();
+        assert_eq!(parsed.primary.creation_timestamp.dtntime(), time);
+    }
+}
+
+#[test]
+fn json_flag_names_tests() {
+    let flags = BundleControlFlags::from_bits_retain(0x1_0000_0002);
+    let names = flag_names(flags);
+    assert_eq!(
+        names,
+        vec!["BUNDLE_ADMINISTRATIVE_RECORD_PAYLOAD", "0x100000000"]
+    );
+    assert_eq!(
+        parse_flag_names::<BundleControlFlags>(&names).map(|f| f.bits()),
+        Ok(0x1_0000_0002)
+    );
+
+    assert!(parse_flag_names::<BundleControlFlags>(&["NO_SUCH_FLAG".to_string()]).is_err());
+    assert!(parse_flag_names::<BlockControlFlags>(&["0x100".to_string()]).is_err());
+}
+
+#[test]
+fn json_schema_tests() {
+    let schema: serde_json::Value = serde_json::from_str(JSON_SCHEMA).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+
+    for crc_type in [CRC_NO, CRC_16, CRC_32] {
+        let mut bndl = new_json_bundle(crc_type);
+        let value: serde_json::Value = serde_json::from_str(&bndl.to_json()).unwrap();
+        let errors: Vec<String> = validator
+            .iter_errors(&value)
+            .map(|e| e.to_string())
+            .collect();
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    // tool reports and times after the year 9999 are covered by the schema as well
+    let mut bndl = new_json_bundle(CRC_16);
+    bndl.primary.creation_timestamp = CreationTimestamp::with_time_and_seq(u64::MAX, 0);
+    let mut json = JsonBundle::from(&bndl);
+    json.report = Some(serde_json::json!({
+        "valid": true,
+        "errors": [],
+        "crc": [{ "number": 0, "status": "valid", "span": [1, 42] }],
+        "administrative_record": null,
+    }));
+    let value = serde_json::to_value(&json).unwrap();
+    assert!(validator.is_valid(&value), "{}", value);
+    let parsed = Bundle::try_from(value.to_string()).unwrap();
+    assert_eq!(parsed.primary, bndl.primary);
+
+    let mut bndl = new_json_bundle(CRC_NO);
+    let mut value: serde_json::Value = serde_json::from_str(&bndl.to_json()).unwrap();
+    value["primary"]["unexpected"] = serde_json::json!(1);
+    assert!(!validator.is_valid(&value));
+}
+
+#[test]
+fn json_invalid_tests() {
+    let mut bndl = new_json_bundle(CRC_32);
+    let json = bndl.to_json();
+
+    let broken = json.replace("dtn://node23/", "foo:bar");
+    assert!(Bundle::try_from(broken).is_err());
+
+    let broken = json.replace("\"crc32\"", "\"crc16\"");
+    assert!(Bundle::try_from(broken).is_err());
+
+    let broken = json.replace("QUJDAP8=", "not base64!");
+    assert!(Bundle::try_from(broken).is_err());
+
+    let broken = json.replace("BLOCK_REPLICATE", "BLOCK_NONSENSE");
+    assert!(Bundle::try_from(broken).is_err());
+
+    let broken = json.replace("\"lifetime_ms\"", "\"lifetime\"");
+    assert!(Bundle::try_from(broken).is_err());
+
+    assert!(Bundle::try_from("{}".to_string()).is_err());
+}
@@ -0,0 +1,119 @@
+use bp7::administrative_record::*;
+use bp7::crc::{CRC_16, CRC_32, CrcBlock};
+use bp7::flags::{BundleControlFlags, BundleValidation};
+use bp7::{Bundle, EndpointID, NodeContext};
+use std::convert::TryFrom;
+use std::time::Duration;
+
+fn eid(s: &str) -> EndpointID {
+    EndpointID::try_from(s).unwrap()
+}
+
+#[test]
+fn node_new_bundle_tests() {
+    let node = NodeContext::new(eid("dtn://node1/"))
+        .crc_type(CRC_32)
+        .lifetime(Duration::from_secs(60))
+        .hop_limit(16);
+
+    let mut bndl = node
+        .new_bundle(eid("dtn://node2/inbox"), b"ABC".to_vec())
+        .unwrap();
+    assert_eq!(bndl.primary.source, eid("dtn://node1/"));
+    assert_eq!(bndl.primary.report_to, eid("dtn://node1/"));
+    assert_eq!(bndl.primary.lifetime, Duration::from_secs(60));
+    assert_eq!(bndl.primary.crc_type(), CRC_32);
+    assert_eq!(bndl.payload().unwrap(), b"ABC");
+    assert_eq!(
+        bndl.extension_block_by_type(bp7::HOP_COUNT_BLOCK)
+            .unwrap()
+            .hop_count_get(),
+        Some((16, 0))
+    );
+
+    let decoded = Bundle::try_from(bndl.to_cbor()).unwrap();
+    decoded.validate().unwrap();
+
+    assert!(
+        node.new_bundle(EndpointID::none(), b"ABC".to_vec())
+            .is_err()
+    );
+    let node = NodeContext::new(eid("dtn://node1/")).lifetime(Duration::ZERO);
+    assert!(
+        node.new_bundle(eid("dtn://node2/inbox"), b"ABC".to_vec())
+            .is_err()
+    );
+}
+
+#[test]
+fn node_sequence_tests() {
+    let node1 = NodeContext::new(eid("dtn://node1/"));
+    let node2 = NodeContext::new(eid("dtn://node2/"));
+
+    let mut seen = std::collections::HashSet::new();
+    for _ in 0..100 {
+        let ts = node1.next_timestamp();
+        assert!(seen.insert((ts.dtntime(), ts.seqno())));
+    }
+
+    // a second node starts its own sequence instead of continuing the first one
+    let b1 = node1.new_bundle(eid("dtn://node3/"), vec![]).unwrap();
+    let b2 = node2.new_bundle(eid("dtn://node3/"), vec![]).unwrap();
+    assert_eq!(b2.primary.creation_timestamp.seqno(), 0);
+    assert_ne!(b1.id(), b2.id());
+}
+
+#[test]
+fn node_status_report_tests() {
+    let sender = NodeContext::new(eid("dtn://node1/"));
+    let receiver = NodeContext::new(eid("dtn://node2/"))
+        .crc_type(CRC_16)
+        .lifetime(Duration::from_secs(60));
+
+    let mut orig = sender
+        .new_bundle(eid("dtn://node2/inbox"), b"ABC".to_vec())
+        .unwrap();
+    orig.primary
+        .bundle_control_flags
+        .set(BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY);
+
+    let report = receiver
+        .status_report_for(&orig, &[DELIVERED_BUNDLE], NO_INFORMATION)
+        .unwrap();
+    assert_eq!(report.primary.source, eid("dtn://node2/"));
+    assert_eq!(report.primary.destination, eid("dtn://node1/"));
+    assert_eq!(report.primary.crc_type(), CRC_16);
+    assert_ne!(report.primary.creation_timestamp.dtntime(), 0);
+    assert_eq!(report.primary.lifetime, Duration::from_secs(60));
+    let decoded = Bundle::try_from(report.clone().to_cbor()).unwrap();
+    decoded.validate().unwrap();
+
+    assert!(
+        receiver
+            .status_report_for(&orig, &[RECEIVED_BUNDLE], NO_INFORMATION)
+            .is_err()
+    );
+}
+
+#[test]
+fn node_forward_update_tests() {
+    let source = NodeContext::new(eid("dtn://node1/")).hop_limit(2);
+    let relay = NodeContext::new(eid("dtn://relay/")).crc_type(CRC_32);
+
+    let mut bndl = source
+        .new_bundle(eid("dtn://node2/inbox"), b"ABC".to_vec())
+        .unwrap();
+    assert!(bndl.previous_node().is_none());
+
+    assert!(relay.forward_update(&mut bndl, Duration::from_millis(10)));
+    assert_eq!(bndl.previous_node(), Some(&eid("dtn://relay/")));
+    assert_eq!(
+        bndl.extension_block_by_type(bp7::HOP_COUNT_BLOCK)
+            .unwrap()
+            .hop_count_get(),
+        Some((2, 1))
+    );
+
+    assert!(relay.forward_update(&mut bndl, Duration::from_millis(10)));
+    assert!(!relay.forward_update(&mut bndl, Duration::from_millis(10)));
+}
@@ -1,5 +1,7 @@
+use bp7::flags::BundleControlFlags;
 use bp7::helpers::unhexify;
-use bp7::{Bundle, primary};
+use bp7::primary::{PrimaryBuilderError, PrimaryDefaults};
+use bp7::{Bundle, CreationTimestamp, EndpointID, primary};
 use std::time::Duration;
 
 #[test]
@@ -40,3 +42,83 @@ fn test_ipn_accept() {
         "ipn:0.<nonzero> should get accepted and treated as ipn:0.0"
     );
 }
+
+struct TestNode;
+
+impl PrimaryDefaults for TestNode {
+    fn source(&self) -> EndpointID {
+        "dtn://node1/app".try_into().unwrap()
+    }
+    fn creation_timestamp(&self) -> CreationTimestamp {
+        CreationTimestamp::with_time_and_seq(42, 7)
+    }
+    fn lifetime(&self) -> Duration {
+        Duration::from_secs(10)
+    }
+}
+
+#[test]
+fn test_builder_defaults() {
+    let dst: EndpointID = "dtn://node2/inbox".try_into().unwrap();
+
+    let p = primary::PrimaryBlockBuilder::new()
+        .destination(dst.clone())
+        .build()
+        .unwrap();
+    assert_eq!(p.source, EndpointID::none());
+    assert_eq!(p.report_to, EndpointID::none());
+    assert_eq!(p.lifetime, primary::DEFAULT_LIFETIME);
+    assert_ne!(p.creation_timestamp.dtntime(), 0);
+
+    let p = primary::PrimaryBlockBuilder::new()
+        .destination(dst.clone())
+        .build_with(&TestNode)
+        .unwrap();
+    assert_eq!(p.source, TestNode.source());
+    assert_eq!(p.report_to, TestNode.source());
+    assert_eq!(p.creation_timestamp, TestNode.creation_timestamp());
+    assert_eq!(p.lifetime, Duration::from_secs(10));
+
+    let report_to: EndpointID = "dtn://node1/reports".try_into().unwrap();
+    let p = primary::PrimaryBlockBuilder::new()
+        .destination(dst)
+        .report_to(report_to.clone())
+        .lifetime(Duration::from_secs(1))
+        .build_with(&TestNode)
+        .unwrap();
+    assert_eq!(p.source, TestNode.source());
+    assert_eq!(p.report_to, report_to);
+    assert_eq!(p.lifetime, Duration::from_secs(1));
+}
+
+#[test]
+fn test_builder_validation() {
+    assert!(matches!(
+        primary::PrimaryBlockBuilder::new().build(),
+        Err(PrimaryBuilderError::NoDestination)
+    ));
+
+    let flags = BundleControlFlags::BUNDLE_ADMINISTRATIVE_RECORD_PAYLOAD
+        | BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY
+        | BundleControlFlags::BUNDLE_CFRESERVED_FIELDS;
+    let res = primary::PrimaryBlockBuilder::new()
+        .destination("dtn://node2/inbox".try_into().unwrap())
+        .bundle_control_flags(flags.bits())
+        .fragmentation_offset(10)
+        .total_data_length(100)
+        .lifetime(Duration::ZERO)
+        .build();
+    match res {
+        Err(PrimaryBuilderError::Invalid(errors)) => assert_eq!(errors.len(), 4),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let p = primary::PrimaryBlockBuilder::new()
+        .destination("dtn://node2/inbox".try_into().unwrap())
+        .bundle_control_flags(BundleControlFlags::BUNDLE_IS_FRAGMENT.bits())
+        .fragmentation_offset(10)
+        .total_data_length(100)
+        .build()
+        .unwrap();
+    assert!(p.has_fragmentation());
+}
@@ -0,0 +1,197 @@
+"""Python counterparts of the vectors in tests/bundle_tests.rs.
+
+Run with `python -m unittest discover tests/python` after building the
+extension module, see the README.
+"""
+
+import time
+import unittest
+
+import bp7
+
+DTN_EPOCH_UNIX = 946684800
+
+
+def dtn_time_now():
+    return int((time.time() - DTN_EPOCH_UNIX) * 1000)
+
+
+def primary(timestamp=None):
+    return (
+        bp7.PrimaryBlockBuilder()
+        .destination("dtn://node2/inbox")
+        .source("dtn://node1/123456")
+        .report_to("dtn://node1/123456")
+        .creation_timestamp(dtn_time_now() if timestamp is None else timestamp, 0)
+        .lifetime(60 * 60 * 1000)
+    )
+
+
+def new_complete_bundle(crc_type):
+    bndl = bp7.Bundle(
+        primary(),
+        [
+            bp7.CanonicalBlock.bundle_age(2, 0),
+            bp7.CanonicalBlock.hop_count(3, 16),
+            bp7.CanonicalBlock.previous_node(4, "dtn://node23"),
+            bp7.CanonicalBlock.payload(b"ABC"),
+        ],
+        crc_type,
+    )
+    bndl.validate()
+    return bndl
+
+
+def new_empty_bundle(crc_type):
+    bndl = bp7.Bundle(primary(), [], crc_type)
+    assert not bndl.is_valid()
+    return bndl
+
+
+def new_complete_bundle_invalid(crc_type):
+    return bp7.Bundle(
+        primary(),
+        [
+            bp7.CanonicalBlock.payload(b"ABC"),
+            bp7.CanonicalBlock.bundle_age(2, 0),
+            bp7.CanonicalBlock.hop_count(2, 16),
+            bp7.CanonicalBlock.previous_node(2, "dtn://node23"),
+        ],
+        crc_type,
+    )
+
+
+class BundleTests(unittest.TestCase):
+    def test_roundtrip(self):
+        for crc_type in (bp7.CRC_NO, bp7.CRC_16, bp7.CRC_32):
+            bndl = new_complete_bundle(crc_type)
+            decoded = bp7.Bundle.from_cbor(bndl.to_cbor())
+            self.assertEqual(bndl, decoded)
+            self.assertEqual(decoded.crc_type, crc_type)
+            decoded.validate()
+
+    def test_rfc_example(self):
+        # same bundle as the example in the crate documentation
+        pb = (
+            bp7.PrimaryBlockBuilder()
+            .flags(0x000004 | 0x020000)
+            .destination("dtn://node2/inbox")
+            .source("dtn://node1/123456")
+            .report_to(bp7.EndpointID("dtn://node1/123456"))
+            .creation_timestamp(0, 0)
+            .lifetime(60 * 60 * 1000)
+        )
+        bndl = bp7.Bundle(pb, [bp7.CanonicalBlock.payload(b"ABC")], bp7.CRC_16)
+        expected = bytes(
+            [159, 137, 7, 26, 0, 2, 0, 4, 1, 130, 1, 109, 47, 47, 110, 111, 100, 101, 50,
+             47, 105, 110, 98, 111, 120, 130, 1, 110, 47, 47, 110, 111, 100, 101, 49, 47, 49,
+             50, 51, 52, 53, 54, 130, 1, 110, 47, 47, 110, 111, 100, 101, 49, 47, 49, 50, 51,
+             52, 53, 54, 130, 0, 0, 26, 0, 54, 238, 128, 66, 188, 152, 134, 1, 1, 0, 1, 67, 65,
+             66, 67, 66, 15, 86, 255]
+        )
+        self.assertEqual(bndl.to_cbor(), expected)
+
+    def test_bundle_id(self):
+        pb = (
+            bp7.PrimaryBlockBuilder()
+            .destination("dtn://node2/inbox")
+            .source("dtn://node1/outbox")
+            .creation_timestamp(0, 0)
+        )
+        bndl = bp7.Bundle(pb, [])
+        self.assertEqual(bndl.id, "dtn://node1/outbox-0-0")
+        self.assertEqual(str(bndl.source), "dtn://node1/outbox")
+        self.assertEqual(bndl.destination, bp7.EndpointID("dtn://node2/inbox"))
+
+    def test_helpers(self):
+        self.assertIsNotNone(new_complete_bundle(bp7.CRC_NO).previous_node)
+        self.assertIsNone(new_empty_bundle(bp7.CRC_NO).previous_node)
+
+    def test_invalid_cbor(self):
+        with self.assertRaises(bp7.Bp7Error):
+            bp7.Bundle.from_cbor(b"\x41\x41")
+        with self.assertRaises(ValueError):
+            bp7.EndpointID("foo:bar")
+
+    def test_invalid_block_numbers(self):
+        for crc_type in (bp7.CRC_NO, bp7.CRC_16, bp7.CRC_32):
+            bndl = new_complete_bundle_invalid(crc_type)
+            self.assertFalse(bndl.is_valid())
+            with self.assertRaises(bp7.Bp7Error):
+                bndl.validate()
+
+    def test_canonical_update(self):
+        bndl = new_complete_bundle(bp7.CRC_NO)
+        self.assertTrue(bndl.update_extensions("dtn://newnode", 23))
+        self.assertEqual(bndl.hop_count, (16, 1))
+        self.assertEqual(bndl.block(bp7.BUNDLE_AGE_BLOCK).data, 23)
+        self.assertEqual(bndl.previous_node, bp7.EndpointID("dtn://newnode"))
+
+    def test_add_block(self):
+        bndl = new_empty_bundle(bp7.CRC_NO)
+        bndl.add_block(bp7.CanonicalBlock.hop_count(666, 16))
+        self.assertEqual(len(bndl.blocks), 1)
+        # already present, should be ignored
+        bndl.add_block(bp7.CanonicalBlock.hop_count(666, 16))
+        self.assertEqual(len(bndl.blocks), 1)
+        bndl.add_block(bp7.CanonicalBlock.bundle_age(666, 0))
+        bndl.add_block(bp7.CanonicalBlock.payload(b"ABC"))
+        self.assertEqual([b.block_number for b in bndl.blocks], [3, 2, 1])
+
+        bndl = new_complete_bundle(bp7.CRC_NO)
+        self.assertEqual(bndl.payload, b"ABC")
+        bndl.set_payload(b"123")
+        self.assertEqual(bndl.payload, b"123")
+        self.assertEqual(bndl.remove_block(3).block_type, bp7.HOP_COUNT_BLOCK)
+        self.assertIsNone(bndl.hop_count)
+
+    def test_lifetime(self):
+        bndl = bp7.Bundle(
+            primary(timestamp=0),
+            [bp7.CanonicalBlock.bundle_age(2, 0), bp7.CanonicalBlock.payload(b"ABC")],
+        )
+        self.assertEqual(bndl.age, 0)
+        self.assertTrue(bndl.update_extensions("dtn://newnode", 30 * 60 * 1000))
+        self.assertEqual(bndl.age, 30 * 60 * 1000)
+        self.assertFalse(bndl.update_extensions("dtn://newnode", 30 * 60 * 1000))
+        self.assertTrue(bndl.is_lifetime_exceeded())
+
+    def test_to_dict(self):
+        d = new_complete_bundle(bp7.CRC_32).to_dict()
+        self.assertEqual(d["primary"]["destination"], "dtn://node2/inbox")
+        self.assertEqual(d["primary"]["crc_type"], bp7.CRC_32)
+        blocks = {b["block_type_name"]: b["data"] for b in d["blocks"]}
+        self.assertEqual(blocks["payload"], b"ABC")
+        self.assertEqual(blocks["hop_count"], {"limit": 16, "count": 0})
+        self.assertEqual(blocks["previous_node"], "dtn://node23/")
+
+
+class StatusReportTests(unittest.TestCase):
+    def test_status_report(self):
+        pb = primary().flags(0x020000)  # request delivery reports
+        orig = bp7.Bundle(pb, [bp7.CanonicalBlock.payload(b"ABC")])
+        self.assertIsNone(orig.status_report())
+
+        with self.assertRaises(bp7.Bp7Error):
+            bp7.status_report_bundle(orig, "dtn://node2/", ["received"])
+
+        report = bp7.status_report_bundle(
+            orig, "dtn://node2/", ["received", bp7.DELIVERED_BUNDLE], bp7.NO_INFORMATION
+        )
+        self.assertTrue(report.is_administrative_record())
+        sr = bp7.Bundle.from_cbor(report.to_cbor()).status_report()
+        self.assertEqual(sr["asserted"], ["delivered"])
+        self.assertEqual(sr["refbundle"], orig.id)
+        self.assertEqual(sr["reason_name"], "no_information")
+
+        forced = bp7.status_report_bundle(
+            orig, "dtn://node2/", ["received"], bp7.DEPLETED_STORAGE, force=True
+        )
+        sr = forced.status_report()
+        self.assertEqual(sr["asserted"], ["received"])
+        self.assertEqual(sr["reason"], bp7.DEPLETED_STORAGE)
+        self.assertEqual(bp7.status_report_reason_name(sr["reason"]), "depleted_storage")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -1,9 +1,14 @@
+#[cfg(feature = "bpsec")]
 use bp7::flags::*;
 #[cfg(feature = "bpsec")]
 use bp7::security::*;
+#[cfg(feature = "bpsec")]
 use bp7::*;
+#[cfg(feature = "bpsec")]
 use helpers::*;
+#[cfg(feature = "bpsec")]
 use std::convert::TryInto;
+#[cfg(feature = "bpsec")]
 use std::time::Duration;
 //use bp7::security::AES_128_GCM;
 
@@ -17,6 +22,7 @@ fn security_data_tests() {
     assert_eq!(data, decoded_data);
 }
 
+#[cfg(feature = "bpsec")]
 fn encode_decode_test_canonical(data: CanonicalBlock) {
     let encoded_data = serde_cbor::to_vec(&data).expect("encoding error");
     let decoded_data: CanonicalBlock =
@@ -195,3 +201,113 @@ fn simple_integrity_test() {
     let example_bundle = "9f88070000820282010282028202018202820201820018281a000f4240850b0200005856810101018202820201828201078203008181820158403bdc69b3a34a2b5d3a8554368bd1e808f606219d2a10a846eae3886ae4ecc83c4ee550fdfb1cc636b904e2f1a73e303dcd4b6ccece003e95e8164dcc89a156e185010100005823526561647920746f2067656e657261746520612033322d62797465207061796c6f6164ff";
     assert_eq!(cbor_bundle, example_bundle);
 }
+
+#[test]
+#[cfg(feature = "bpsec")]
+fn integrity_block_verify_tests() {
+    // RFC 9173 Example 1 bundle, signed with key 1a2b1a2b1a2b1a2b1a2b1a2b1a2b1a2b
+    let example_bundle = "9f88070000820282010282028202018202820201820018281a000f4240850b0200005856810101018202820201828201078203008181820158403bdc69b3a34a2b5d3a8554368bd1e808f606219d2a10a846eae3886ae4ecc83c4ee550fdfb1cc636b904e2f1a73e303dcd4b6ccece003e95e8164dcc89a156e185010100005823526561647920746f2067656e657261746520612033322d62797465207061796c6f6164ff";
+    let key: [u8; 16] = unhexify("1a2b1a2b1a2b1a2b1a2b1a2b1a2b1a2b")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let mut bndl: Bundle = unhexify(example_bundle).unwrap().try_into().unwrap();
+
+    let bib_data = match bndl
+        .extension_block_by_type(INTEGRITY_BLOCK)
+        .unwrap()
+        .data()
+    {
+        CanonicalData::Unknown(data) => data.clone(),
+        _ => panic!("BIB not decoded as unknown block"),
+    };
+    let bib = IntegrityBlock::from_cbor(&bib_data).unwrap();
+    assert_eq!(bib.security_targets, vec![1]);
+    assert_eq!(bib.to_cbor(), bib_data);
+
+    assert!(bib.verify(&bndl, 2, key).unwrap());
+    assert!(!bib.verify(&bndl, 2, [0; 16]).unwrap());
+    assert!(matches!(
+        bib.verify(&bndl, 1, key),
+        Err(IntegrityBlockError::NotIntegrityBlock(1))
+    ));
+
+    bndl.set_payload(b"Ready to generate a 32-byte payloaD".to_vec());
+    assert!(!bib.verify(&bndl, 2, key).unwrap());
+
+    assert!(IntegrityBlock::from_cbor(&[0x81, 0x01]).is_err());
+}
+
+/// Adds a BIB-HMAC-SHA2 block protecting the given targets, returns its block number.
+#[cfg(feature = "bpsec")]
+fn add_bib(b: &mut Bundle, targets: &[u64], key: [u8; 16]) -> u64 {
+    let block_number = b.canonicals.iter().map(|c| c.block_number).max().unwrap() + 1;
+    let ippts: Vec<(u64, ByteBuffer)> = targets
+        .iter()
+        .map(|t| {
+            let mut ippt = IpptBuilder::default()
+                .primary_block(b.primary.clone())
+                .security_header((INTEGRITY_BLOCK, block_number, 0))
+                .scope_flags(0x0007)
+                .build();
+            (*t, ippt.create(b.block(*t).unwrap()))
+        })
+        .collect();
+    let mut bib = IntegrityBlockBuilder::default()
+        .security_targets(targets.to_vec())
+        .security_context_flags(SEC_CONTEXT_PRESENT)
+        .security_source(b.primary.source.clone())
+        .security_context_parameters(BibSecurityContextParameter::new(
+            Some((1, HMAC_SHA_256)),
+            None,
+            Some((3, 0x0007)),
+        ))
+        .build()
+        .unwrap();
+    bib.compute_hmac(key, ippts.iter().map(|(t, i)| (*t, i)).collect());
+    b.canonicals.push(new_integrity_block(
+        block_number,
+        BlockControlFlags::empty(),
+        bib.to_cbor(),
+    ));
+    b.sort_canonicals();
+    block_number
+}
+
+#[test]
+#[cfg(feature = "bpsec")]
+fn remove_block_updates_bib_targets() {
+    let key = [0x1a; 16];
+    let mut b = rnd_bundle(dtntime::CreationTimestamp::now());
+    let hop_count = b
+        .extension_block_by_type(HOP_COUNT_BLOCK)
+        .unwrap()
+        .block_number;
+    let prev_node = b
+        .insert_block(new_previous_node_block(
+            0,
+            BlockControlFlags::empty(),
+            EndpointID::with_dtn("node3").unwrap(),
+        ))
+        .unwrap();
+    let both = add_bib(&mut b, &[hop_count, 1], key);
+    let single = add_bib(&mut b, &[prev_node], key);
+
+    let decode = |b: &Bundle, n: u64| match b.block(n).unwrap().data() {
+        CanonicalData::Unknown(data) => IntegrityBlock::from_cbor(data).unwrap(),
+        _ => panic!("not an integrity block"),
+    };
+    assert!(decode(&b, both).verify(&b, both, key).unwrap());
+
+    assert!(b.remove_block(hop_count).is_some());
+    let bib = decode(&b, both);
+    assert_eq!(bib.security_targets, vec![1]);
+    assert_eq!(bib.security_results.len(), 1);
+    assert!(bib.verify(&b, both, key).unwrap());
+
+    // integrity blocks without targets are removed as well
+    assert!(b.remove_block(prev_node).is_some());
+    assert!(b.block(single).is_none());
+    assert!(b.block(both).is_some());
+    assert!(b.validate().is_ok());
+}
@@ -0,0 +1,135 @@
+#![cfg(target_arch = "wasm32")]
+
+use bp7::wasm::*;
+use wasm_bindgen_test::*;
+
+fn new_bundle(flags: u64) -> JsBundle {
+    JsPrimaryBlockBuilder::new()
+        .destination("dtn://node2/inbox")
+        .unwrap()
+        .source("dtn://node1/app")
+        .unwrap()
+        .report_to("dtn://node1/app")
+        .unwrap()
+        .flags(flags)
+        .creation_timestamp(0, 7)
+        .lifetime(60 * 60 * 1000)
+        .build(b"ABC".to_vec(), bp7::crc::CRC_32)
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn wasm_builder_tests() {
+    assert!(JsPrimaryBlockBuilder::new().destination("foo:bar").is_err());
+    assert!(JsPrimaryBlockBuilder::new().build(vec![], 0).is_err());
+
+    let mut bndl = new_bundle(0);
+    assert_eq!(bndl.id(), "dtn://node1/app-0-7");
+    assert_eq!(bndl.source().to_js_string(), "dtn://node1/app");
+    assert!(
+        bndl.destination()
+            .equals(&JsEndpointID::new("dtn://node2/inbox").unwrap())
+    );
+    assert_eq!(bndl.crc_type(), bp7::crc::CRC_32);
+    assert_eq!(bndl.payload().unwrap(), b"ABC");
+
+    bndl.set_lifetime(1000);
+    bndl.set_flags(bp7::flags::BundleControlFlags::BUNDLE_MUST_NOT_FRAGMENTED.bits());
+    assert!(bndl.set_crc(7).is_err());
+    bndl.set_crc(bp7::crc::CRC_16).unwrap();
+    bndl.add_bundle_age_block(0).unwrap();
+    bndl.validate().unwrap();
+
+    let decoded = JsBundle::from_cbor(&bndl.to_cbor()).unwrap();
+    assert_eq!(decoded, bndl);
+    assert_eq!(decoded.lifetime(), 1000);
+    assert_eq!(decoded.crc_type(), bp7::crc::CRC_16);
+
+    let js = decoded.to_js_value().unwrap();
+    assert_eq!(bid_from_bundle(&js).unwrap(), "dtn://node1/app-0-7");
+    assert_eq!(JsBundle::from_js_value(js).unwrap(), decoded);
+}
+
+#[wasm_bindgen_test]
+fn wasm_extension_block_tests() {
+    let mut bndl = new_bundle(0);
+    assert_eq!(bndl.hop_count(), None);
+    assert!(bndl.add_hop_count_block(0).is_err());
+    bndl.add_hop_count_block(16).unwrap();
+    assert!(bndl.add_hop_count_block(16).is_err());
+    bndl.add_bundle_age_block(10).unwrap();
+    bndl.add_previous_node_block("dtn://node1/").unwrap();
+    assert!(bndl.add_extension_block(1, 0, vec![]).is_err());
+    bndl.add_extension_block(192, 0, vec![1, 2, 3]).unwrap();
+    assert_eq!(bndl.block_numbers(), vec![5, 4, 3, 2, 1]);
+    assert!(bndl.is_valid());
+
+    assert!(bndl.update_extensions("dtn://node3/", 5).unwrap());
+    let mut decoded = JsBundle::from_cbor(&bndl.to_cbor()).unwrap();
+    assert_eq!(
+        decoded.hop_count(),
+        Some(HopCount {
+            limit: 16,
+            count: 1
+        })
+    );
+    assert_eq!(decoded.age(), Some(15));
+    assert_eq!(
+        decoded.previous_node().unwrap().to_js_string(),
+        "dtn://node3/"
+    );
+
+    assert!(decoded.remove_block(5));
+    assert!(!decoded.remove_block(1));
+    assert_eq!(decoded.block_numbers(), vec![4, 3, 2, 1]);
+    decoded.validate().unwrap();
+}
+
+#[wasm_bindgen_test]
+fn wasm_status_report_tests() {
+    let orig = new_bundle(bp7::flags::BundleControlFlags::BUNDLE_STATUS_REQUEST_DELIVERY.bits());
+    assert!(orig.status_report().unwrap().is_none());
+
+    assert!(
+        orig.new_status_report(
+            "dtn://node2/",
+            vec![StatusInformation::Received],
+            0,
+            0,
+            false
+        )
+        .is_err()
+    );
+
+    let mut report = orig
+        .new_status_report(
+            "dtn://node2/",
+            vec![StatusInformation::Received, StatusInformation::Delivered],
+            bp7::administrative_record::NO_INFORMATION,
+            bp7::crc::CRC_16,
+            false,
+        )
+        .unwrap();
+    assert!(report.is_admin_record());
+    let decoded = JsBundle::from_cbor(&report.to_cbor()).unwrap();
+    let sr = decoded.status_report().unwrap().unwrap();
+    assert_eq!(sr.asserted(), vec![StatusInformation::Delivered]);
+    assert!(sr.is_asserted(StatusInformation::Delivered));
+    assert_eq!(sr.status_time(StatusInformation::Delivered), None);
+    assert_eq!(sr.refbundle(), orig.id());
+    assert_eq!(sr.reason_name().as_deref(), Some("no_information"));
+    assert_eq!(sr.sequence_number(), 7);
+
+    let forced = orig
+        .new_status_report(
+            "dtn://node2/",
+            vec![StatusInformation::Deleted],
+            bp7::administrative_record::DEPLETED_STORAGE,
+            bp7::crc::CRC_NO,
+            true,
+        )
+        .unwrap();
+    let sr = forced.status_report().unwrap().unwrap();
+    assert_eq!(sr.asserted(), vec![StatusInformation::Deleted]);
+    assert_eq!(sr.reason(), bp7::administrative_record::DEPLETED_STORAGE);
+}